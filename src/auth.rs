@@ -0,0 +1,567 @@
+//! Microsoft account authentication, from a Microsoft OAuth token to a Minecraft access token and profile.
+//!
+//! The full chain is: Microsoft OAuth (optionally through the device code flow) → Xbox Live user
+//! authentication → XSTS authorization → `login_with_xbox` → `/minecraft/profile`.
+//! Every step's base URL can be overridden, so that the whole flow can be run against a mock server.
+//!
+//! Only the Minecraft steps are subject to the client's [rate limit](crate::RateLimit) and [retries](crate::RetryPolicy);
+//! the Microsoft and Xbox Live requests are sent once, directly through the client's transport.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::online::{check_status, parse_json, trim_url};
+#[cfg(feature = "async")]
+use crate::transport::AsyncHttpTransport;
+#[cfg(feature = "online")]
+use crate::transport::HttpTransport;
+use crate::transport::{DefaultTransport, HttpRequest, HttpResponse};
+use crate::{Error, MojangClient, ProfileSummary, Result};
+
+/// The default base URL of Microsoft's OAuth 2.0 endpoints, for personal Microsoft accounts.
+pub const MICROSOFT_OAUTH_URL: &str = "https://login.microsoftonline.com/consumers/oauth2/v2.0";
+
+/// The default base URL of Xbox Live user authentication.
+pub const XBOX_USER_AUTH_URL: &str = "https://user.auth.xboxlive.com";
+
+/// The default base URL of Xbox Live security token service (XSTS) authorization.
+pub const XSTS_AUTH_URL: &str = "https://xsts.auth.xboxlive.com";
+
+/// The OAuth scopes needed to sign in to Xbox Live.
+const SCOPE: &str = "XboxLive.signin offline_access";
+
+/// A pending device code authorization, which the user completes by visiting `verification_uri`
+/// and entering `user_code`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DeviceCode {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    /// The number of seconds after which the code expires.
+    pub expires_in: u64,
+    /// The number of seconds to wait between polls.
+    pub interval: u64,
+    /// A message with instructions for the user, in their language.
+    pub message: String,
+}
+
+/// A Microsoft OAuth token, as returned by the device code flow.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MicrosoftToken {
+    pub access_token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    /// The number of seconds after which the access token expires.
+    pub expires_in: u64,
+}
+
+/// The result of a successful login: a Minecraft access token, and the profile it belongs to.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MinecraftSession {
+    pub access_token: String,
+    /// The number of seconds after which the access token expires.
+    pub expires_in: u64,
+    pub profile: ProfileSummary,
+}
+
+#[derive(Deserialize)]
+struct OAuthError {
+    error: String,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct XboxLiveResponse {
+    token: String,
+    display_claims: XboxLiveClaims,
+}
+
+#[derive(Deserialize)]
+struct XboxLiveClaims {
+    xui: Vec<XboxLiveUser>,
+}
+
+#[derive(Deserialize)]
+struct XboxLiveUser {
+    uhs: String,
+}
+
+#[derive(Deserialize)]
+struct XstsError {
+    #[serde(rename = "XErr")]
+    xerr: u64,
+}
+
+#[derive(Deserialize)]
+struct LoginResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+/// The state of a device code authorization that is being polled.
+enum Poll {
+    Done(MicrosoftToken),
+    Pending,
+    SlowDown,
+}
+
+/// Authenticates Microsoft accounts with Minecraft.
+///
+/// A Microsoft OAuth client ID (registered in Azure, with Xbox Live access) is required.
+///
+/// # Examples
+/// Logging in with the device code flow:
+/// ```rust,no_run
+/// use uuid_mc::auth::MicrosoftAuth;
+///
+/// # fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+/// let auth = MicrosoftAuth::new("00000000-0000-0000-0000-000000000000");
+///
+/// let code = auth.request_device_code()?;
+/// println!("{}", code.message);
+/// let token = auth.wait_for_device_code(&code)?;
+///
+/// let session = auth.login(&token.access_token)?;
+/// println!("logged in as {}", session.profile.name);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MicrosoftAuth<T = DefaultTransport> {
+    client: MojangClient<T>,
+    client_id: String,
+    oauth_url: String,
+    xbox_user_url: String,
+    xsts_url: String,
+}
+
+impl MicrosoftAuth {
+    /// Creates an authenticator for the given OAuth client ID, using a default [`MojangClient`].
+    pub fn new(client_id: impl Into<String>) -> Self {
+        Self::with_client(MojangClient::new(), client_id)
+    }
+}
+
+impl<T> MicrosoftAuth<T> {
+    /// Creates an authenticator for the given OAuth client ID, which sends its requests through the provided client.
+    ///
+    /// The client's transport and request settings are used for every step, and its
+    /// [services URL](crate::MojangClientBuilder::services_url) for the Minecraft ones.
+    pub fn with_client(client: MojangClient<T>, client_id: impl Into<String>) -> Self {
+        Self {
+            client,
+            client_id: client_id.into(),
+            oauth_url: MICROSOFT_OAUTH_URL.to_owned(),
+            xbox_user_url: XBOX_USER_AUTH_URL.to_owned(),
+            xsts_url: XSTS_AUTH_URL.to_owned(),
+        }
+    }
+
+    /// Sets the base URL of the Microsoft OAuth 2.0 endpoints.
+    pub fn oauth_url(mut self, url: impl Into<String>) -> Self {
+        self.oauth_url = trim_url(url.into());
+        self
+    }
+
+    /// Sets the base URL of Xbox Live user authentication.
+    pub fn xbox_user_url(mut self, url: impl Into<String>) -> Self {
+        self.xbox_user_url = trim_url(url.into());
+        self
+    }
+
+    /// Sets the base URL of XSTS authorization.
+    pub fn xsts_url(mut self, url: impl Into<String>) -> Self {
+        self.xsts_url = trim_url(url.into());
+        self
+    }
+
+    fn device_code_request(&self) -> HttpRequest {
+        self.client.post_form(
+            format!("{}/devicecode", self.oauth_url),
+            &[("client_id", &self.client_id), ("scope", SCOPE)],
+        )
+    }
+
+    fn poll_request(&self, code: &DeviceCode) -> HttpRequest {
+        self.client.post_form(
+            format!("{}/token", self.oauth_url),
+            &[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+                ("client_id", &self.client_id),
+                ("device_code", &code.device_code),
+            ],
+        )
+    }
+
+    fn xbox_user_request(&self, microsoft_token: &str) -> HttpRequest {
+        let body = json!({
+            "Properties": {
+                "AuthMethod": "RPS",
+                "SiteName": "user.auth.xboxlive.com",
+                "RpsTicket": format!("d={microsoft_token}"),
+            },
+            "RelyingParty": "http://auth.xboxlive.com",
+            "TokenType": "JWT",
+        });
+        self.client
+            .post_json(format!("{}/user/authenticate", self.xbox_user_url), &body)
+            .header("Accept", "application/json")
+    }
+
+    fn xsts_request(&self, xbox_user_token: &str) -> HttpRequest {
+        let body = json!({
+            "Properties": {
+                "SandboxId": "RETAIL",
+                "UserTokens": [xbox_user_token],
+            },
+            "RelyingParty": "rp://api.minecraftservices.com/",
+            "TokenType": "JWT",
+        });
+        self.client
+            .post_json(format!("{}/xsts/authorize", self.xsts_url), &body)
+            .header("Accept", "application/json")
+    }
+
+    fn login_request(&self, xsts: &XboxLiveResponse) -> Result<HttpRequest> {
+        let user = xsts
+            .display_claims
+            .xui
+            .first()
+            .ok_or_else(|| Error::AuthenticationFailed {
+                reason: "XSTS response has no user hash".to_owned(),
+            })?;
+        let body = json!({ "identityToken": format!("XBL3.0 x={};{}", user.uhs, xsts.token) });
+
+        Ok(self.client.post_json(
+            format!(
+                "{}/authentication/login_with_xbox",
+                self.client.services_url()
+            ),
+            &body,
+        ))
+    }
+
+    fn profile_request(&self, access_token: &str) -> HttpRequest {
+        self.client
+            .request(format!("{}/minecraft/profile", self.client.services_url()))
+            .header("Authorization", format!("Bearer {access_token}"))
+    }
+
+    /// Starts the device code flow, returning the code that the user has to enter.
+    ///
+    /// # Errors
+    /// See [`Error`](enum@Error) for the ways the request can fail.
+    #[cfg(feature = "online")]
+    pub fn request_device_code(&self) -> Result<DeviceCode>
+    where
+        T: HttpTransport,
+    {
+        parse_json(self.client.send_external(self.device_code_request())?)
+    }
+
+    /// The `async` equivalent of [`request_device_code`](Self::request_device_code).
+    #[cfg(feature = "async")]
+    pub async fn request_device_code_async(&self) -> Result<DeviceCode>
+    where
+        T: AsyncHttpTransport,
+    {
+        parse_json(
+            self.client
+                .send_external_async(self.device_code_request())
+                .await?,
+        )
+    }
+
+    /// Polls Microsoft until the user completes the device code authorization, returning the resulting token.
+    ///
+    /// # Errors
+    /// If the user declines the authorization, doesn't complete it before the code expires,
+    /// or the code's expiry is too far in the future to represent, an [`Error::AuthenticationFailed`] is returned.
+    /// See [`Error`](enum@Error) for the other ways the request can fail.
+    #[cfg(feature = "online")]
+    pub fn wait_for_device_code(&self, code: &DeviceCode) -> Result<MicrosoftToken>
+    where
+        T: HttpTransport,
+    {
+        let deadline = device_code_deadline(code)?;
+        let mut interval = Duration::from_secs(code.interval);
+        while Instant::now() < deadline {
+            match parse_poll(self.client.send_external(self.poll_request(code))?)? {
+                Poll::Done(token) => return Ok(token),
+                Poll::Pending => {}
+                Poll::SlowDown => interval += Duration::from_secs(5),
+            }
+            std::thread::sleep(interval);
+        }
+
+        Err(device_code_expired())
+    }
+
+    /// The `async` equivalent of [`wait_for_device_code`](Self::wait_for_device_code).
+    #[cfg(feature = "async")]
+    pub async fn wait_for_device_code_async(&self, code: &DeviceCode) -> Result<MicrosoftToken>
+    where
+        T: AsyncHttpTransport,
+    {
+        let deadline = device_code_deadline(code)?;
+        let mut interval = Duration::from_secs(code.interval);
+        while Instant::now() < deadline {
+            match parse_poll(
+                self.client
+                    .send_external_async(self.poll_request(code))
+                    .await?,
+            )? {
+                Poll::Done(token) => return Ok(token),
+                Poll::Pending => {}
+                Poll::SlowDown => interval += Duration::from_secs(5),
+            }
+            crate::online::sleep_async(interval).await;
+        }
+
+        Err(device_code_expired())
+    }
+
+    /// Exchanges a Microsoft OAuth access token for a Minecraft access token, through Xbox Live,
+    /// and fetches the profile it belongs to.
+    ///
+    /// # Errors
+    /// If Xbox Live refuses to authorize the account (for example because it has no Xbox profile, or is a child
+    /// account outside of a family), an [`Error::AuthenticationFailed`] is returned.
+    /// If the account doesn't own Minecraft, an [`Error::NotFound`] is returned.
+    /// See [`Error`](enum@Error) for the other ways the request can fail.
+    #[cfg(feature = "online")]
+    pub fn login(&self, microsoft_token: &str) -> Result<MinecraftSession>
+    where
+        T: HttpTransport,
+    {
+        let xbox_user: XboxLiveResponse = parse_json(
+            self.client
+                .send_external(self.xbox_user_request(microsoft_token))?,
+        )?;
+        let xsts = parse_xsts(
+            self.client
+                .send_external(self.xsts_request(&xbox_user.token))?,
+        )?;
+        let login: LoginResponse = parse_json(self.client.send(self.login_request(&xsts)?)?)?;
+        let profile = parse_json(
+            self.client
+                .send(self.profile_request(&login.access_token))?,
+        )?;
+
+        Ok(MinecraftSession {
+            access_token: login.access_token,
+            expires_in: login.expires_in,
+            profile,
+        })
+    }
+
+    /// The `async` equivalent of [`login`](Self::login).
+    #[cfg(feature = "async")]
+    pub async fn login_async(&self, microsoft_token: &str) -> Result<MinecraftSession>
+    where
+        T: AsyncHttpTransport,
+    {
+        let xbox_user: XboxLiveResponse = parse_json(
+            self.client
+                .send_external_async(self.xbox_user_request(microsoft_token))
+                .await?,
+        )?;
+        let xsts = parse_xsts(
+            self.client
+                .send_external_async(self.xsts_request(&xbox_user.token))
+                .await?,
+        )?;
+        let login: LoginResponse =
+            parse_json(self.client.send_async(self.login_request(&xsts)?).await?)?;
+        let profile = parse_json(
+            self.client
+                .send_async(self.profile_request(&login.access_token))
+                .await?,
+        )?;
+
+        Ok(MinecraftSession {
+            access_token: login.access_token,
+            expires_in: login.expires_in,
+            profile,
+        })
+    }
+}
+
+/// Parses a device code token response, where OAuth reports a pending authorization as a `400 Bad Request`.
+fn parse_poll(response: HttpResponse) -> Result<Poll> {
+    if response.status == 400 {
+        if let Ok(error) = serde_json::from_slice::<OAuthError>(&response.body) {
+            return match error.error.as_str() {
+                "authorization_pending" => Ok(Poll::Pending),
+                "slow_down" => Ok(Poll::SlowDown),
+                _ => Err(Error::AuthenticationFailed {
+                    reason: error.error_description.unwrap_or(error.error),
+                }),
+            };
+        }
+    }
+
+    parse_json(response).map(Poll::Done)
+}
+
+/// Parses an XSTS response, where Xbox Live reports unauthorized accounts as a `401 Unauthorized` with an `XErr` code.
+fn parse_xsts(response: HttpResponse) -> Result<XboxLiveResponse> {
+    if response.status == 401 {
+        if let Ok(error) = serde_json::from_slice::<XstsError>(&response.body) {
+            let reason = match error.xerr {
+                2148916233 => "the account doesn't have an Xbox profile".to_owned(),
+                2148916235 => "Xbox Live is not available in the account's country".to_owned(),
+                2148916236 | 2148916237 => "the account needs adult verification".to_owned(),
+                2148916238 => {
+                    "the account is a child account and must be added to a family".to_owned()
+                }
+                xerr => format!("XSTS authorization failed with XErr {xerr}"),
+            };
+            return Err(Error::AuthenticationFailed { reason });
+        }
+    }
+
+    check_status(response).and_then(parse_json)
+}
+
+/// The instant a device code expires at, which has to be representable for polling to stop.
+fn device_code_deadline(code: &DeviceCode) -> Result<Instant> {
+    Instant::now()
+        .checked_add(Duration::from_secs(code.expires_in))
+        .ok_or_else(|| Error::AuthenticationFailed {
+            reason: format!(
+                "the device code expiry of {}s is out of range",
+                code.expires_in
+            ),
+        })
+}
+
+fn device_code_expired() -> Error {
+    Error::AuthenticationFailed {
+        reason: "the device code expired".to_owned(),
+    }
+}
+
+#[cfg(all(test, feature = "online"))]
+mod tests {
+    use super::*;
+    use crate::tests::MockTransport;
+
+    #[test]
+    fn device_code_login() {
+        let transport = MockTransport::new()
+            .respond(
+                200,
+                r#"{"device_code":"dc","user_code":"ABCD-EFGH","verification_uri":"https://microsoft.com/link",
+                    "expires_in":900,"interval":0,"message":"Go to https://microsoft.com/link"}"#,
+            )
+            .respond(400, r#"{"error":"authorization_pending"}"#)
+            .respond(200, r#"{"access_token":"ms","refresh_token":"refresh","expires_in":3600}"#)
+            .respond(200, r#"{"Token":"xbl","DisplayClaims":{"xui":[{"uhs":"1234"}]}}"#)
+            .respond(200, r#"{"Token":"xsts","DisplayClaims":{"xui":[{"uhs":"1234"}]}}"#)
+            .respond(200, r#"{"access_token":"minecraft","expires_in":86400}"#)
+            .respond(
+                200,
+                r#"{"id":"069a79f444e94726a5befca90e38aaf5","name":"Notch","skins":[],"capes":[]}"#,
+            );
+        let client = MojangClient::builder()
+            .services_url("http://localhost/services")
+            .build_with(&transport);
+        let auth = MicrosoftAuth::with_client(client, "client")
+            .oauth_url("http://localhost/oauth")
+            .xbox_user_url("http://localhost/xbl")
+            .xsts_url("http://localhost/xsts");
+
+        let code = auth.request_device_code().unwrap();
+        assert_eq!(code.user_code, "ABCD-EFGH");
+        let token = auth.wait_for_device_code(&code).unwrap();
+        assert_eq!(token.refresh_token.as_deref(), Some("refresh"));
+
+        let session = auth.login(&token.access_token).unwrap();
+        assert_eq!(session.access_token, "minecraft");
+        assert_eq!(session.profile.name, "Notch");
+
+        let requests = transport.requests();
+        let urls: Vec<_> = requests
+            .iter()
+            .map(|request| request.url.as_str())
+            .collect();
+        assert_eq!(
+            urls,
+            [
+                "http://localhost/oauth/devicecode",
+                "http://localhost/oauth/token",
+                "http://localhost/oauth/token",
+                "http://localhost/xbl/user/authenticate",
+                "http://localhost/xsts/xsts/authorize",
+                "http://localhost/services/authentication/login_with_xbox",
+                "http://localhost/services/minecraft/profile",
+            ]
+        );
+        assert_eq!(
+            requests[1].body.as_deref(),
+            Some(&b"grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Adevice_code&client_id=client&device_code=dc"[..])
+        );
+        let login: serde_json::Value =
+            serde_json::from_slice(requests[5].body.as_ref().unwrap()).unwrap();
+        assert_eq!(login["identityToken"], "XBL3.0 x=1234;xsts");
+        assert!(requests[6]
+            .headers
+            .contains(&("Authorization".to_owned(), "Bearer minecraft".to_owned())));
+    }
+
+    #[test]
+    fn microsoft_requests_are_not_retried() {
+        let transport = MockTransport::new().respond(503, "");
+        let auth = MicrosoftAuth::with_client(MojangClient::with_transport(&transport), "client");
+
+        assert!(matches!(
+            auth.request_device_code(),
+            Err(Error::ServerError { status: 503, .. })
+        ));
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[test]
+    fn device_code_expiry_out_of_range() {
+        let transport = MockTransport::new();
+        let auth = MicrosoftAuth::with_client(MojangClient::with_transport(&transport), "client");
+        let code = DeviceCode {
+            device_code: "dc".to_owned(),
+            user_code: "ABCD-EFGH".to_owned(),
+            verification_uri: "https://microsoft.com/link".to_owned(),
+            expires_in: u64::MAX,
+            interval: 0,
+            message: "Go to https://microsoft.com/link".to_owned(),
+        };
+
+        assert!(matches!(
+            auth.wait_for_device_code(&code),
+            Err(Error::AuthenticationFailed { reason }) if reason.contains("out of range")
+        ));
+        assert!(transport.requests().is_empty());
+    }
+
+    #[test]
+    fn xsts_errors() {
+        let transport = MockTransport::new()
+            .respond(
+                200,
+                r#"{"Token":"xbl","DisplayClaims":{"xui":[{"uhs":"1234"}]}}"#,
+            )
+            .respond(
+                401,
+                r#"{"Identity":"0","XErr":2148916233,"Message":"","Redirect":""}"#,
+            );
+        let auth = MicrosoftAuth::with_client(MojangClient::with_transport(&transport), "client");
+
+        assert!(matches!(
+            auth.login("ms"),
+            Err(Error::AuthenticationFailed { reason }) if reason.contains("Xbox profile")
+        ));
+    }
+}
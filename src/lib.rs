@@ -1,36 +1,158 @@
 //! This library provides functionality for converting usernames to and from Minecraft UUIDs,
 //! including support for offline and online players.  
-//! You may choose to disable either the `offline` or `online` features if you don't need them.
+//! You may choose to disable either the `offline` or `online` features if you don't need them.  
+//! Enabling the `async` feature adds `async` equivalents of the Mojang API lookups, built on `reqwest`.  
+//! All lookups have a `_with` variant that accepts a custom transport; see the `transport` module.  
+//! To change the API URLs, timeouts or User-Agent, use a `MojangClient`.  
+//! The `textures` feature (enabled by default) decodes skin and cape information from a [`GameProfile`],
+//! and the `verify` feature checks the signatures of its properties against Mojang's Yggdrasil key.  
+//! The `auth` feature (which enables `online`) logs Microsoft accounts in to Minecraft through Xbox Live; see the `auth` module.  
+//! With the resulting access token, the `services` module manages the account's name, skin and cape.  
+//! Without the `std` feature (enabled by default), the crate is `no_std` and only needs `alloc`;
+//! the UUID types, [`Username`] and offline UUIDs keep working, while the online features require `std`.
+//! The `protocol` module encodes UUIDs for Minecraft packets, over `std::io` or, with the `bytes` feature,
+//! over `bytes` buffers.
 //!
 //! To start, head over to [`PlayerUuid`] or look at some of the examples in this crate.
 
-use serde::{Deserialize, Serialize};
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+use ::serde::{Deserialize, Serialize};
 use thiserror::Error;
 use uuid::Version;
 pub use uuid::{self, Uuid};
 
-/// This library's own error enum, which is returned by every function that returns a [`Result`](std::result::Result).
+#[cfg(feature = "auth")]
+pub mod auth;
+#[cfg(feature = "offline")]
+mod md5;
+pub mod nbt;
+#[cfg(feature = "offline")]
+pub mod offline;
+#[cfg(any(feature = "online", feature = "async"))]
+pub mod online;
+pub mod profile;
+#[cfg(any(feature = "std", feature = "bytes"))]
+pub mod protocol;
+#[cfg(any(feature = "online", feature = "async"))]
+mod retry;
+pub mod serde;
+#[cfg(any(feature = "online", feature = "async"))]
+pub mod services;
+#[cfg(feature = "textures")]
+pub mod textures;
+#[cfg(any(feature = "online", feature = "async"))]
+pub mod transport;
+pub mod username;
+#[cfg(feature = "verify")]
+pub mod verify;
+
+pub use profile::{GameProfile, ProfileProperty, SkinModel};
+#[cfg(feature = "textures")]
+pub use textures::{Texture, Textures};
+pub use username::Username;
+#[cfg(feature = "verify")]
+pub use verify::YggdrasilKey;
+
+#[cfg(any(feature = "online", feature = "async"))]
+pub use online::{MojangClient, MojangClientBuilder, NameAvailability, ProfileSummary};
+#[cfg(any(feature = "online", feature = "async"))]
+pub use retry::{RateLimit, RetryPolicy};
+
+#[cfg(feature = "async")]
+use transport::{AsyncHttpTransport, ReqwestTransport};
+#[cfg(feature = "online")]
+use transport::{HttpTransport, UreqTransport};
+
+/// A boxed error from another crate or from a user-provided transport, used as the source of some [`Error`](enum@Error) variants.
+pub type BoxError = Box<dyn core::error::Error + Send + Sync>;
+
+/// This library's own error enum, which is returned by every function that returns a [`Result`](core::result::Result).
+///
+/// The set of variants is the same regardless of which features are enabled.
 #[derive(Debug, Error)]
 pub enum Error {
-    /// An error that signifies that the user has provided an invalid UUID, be it in the wrong format or a non-existent UUID if in an online context.
+    /// An error that signifies that the user has provided an invalid UUID, i.e. one that is neither offline (v3) nor online (v4).
     #[error("invalid uuid")]
     InvalidUuid,
 
-    /// An error that signifies that the user has provided an invalid username to Mojang's API.
+    /// An error that signifies that the user has provided text that isn't a UUID in any of the accepted formats.
+    #[error("malformed uuid")]
+    MalformedUuid,
+
+    /// An error that signifies that the user has provided a username that doesn't follow Minecraft's naming rules.
     #[error("invalid username")]
     InvalidUsername,
 
-    /// A Transport error from [`ureq`].
-    #[cfg(feature = "online")]
-    #[error("ureq transport error: {0}")]
-    Transport(ureq::Transport),
+    /// An error that signifies that the Mojang API has no player matching the provided username or UUID.
+    #[error("no such player")]
+    NotFound,
+
+    /// An error that signifies that the Mojang API rejected the request as malformed (HTTP 400),
+    /// usually because of an invalid username. `body` holds the response body.
+    #[error("bad request: {body}")]
+    BadRequest { body: String },
+
+    /// An error that signifies that the provided access token is invalid or has expired.
+    #[error("invalid or expired access token")]
+    InvalidToken,
+
+    /// An error that signifies that a Microsoft account could not be authenticated with Minecraft,
+    /// for example because Xbox Live refused it or the user declined the device code authorization.
+    #[error("authentication failed: {reason}")]
+    AuthenticationFailed { reason: String },
 
-    /// An error that signifies that the Mojang API returned an unexpected result.
-    #[error("mojang api returned unexpected result")]
-    MojangAPIError,
+    /// An error that signifies that the Mojang API kept rate limiting the request after all retries were exhausted.
+    /// `retry_after` holds the delay requested by the last response, if it specified one.
+    #[error("rate limited by the mojang api")]
+    RateLimited { retry_after: Option<Duration> },
+
+    /// An error that signifies that the Mojang API kept failing with a server error (HTTP 5xx)
+    /// after all retries were exhausted.
+    #[error("mojang api server error {status}: {body}")]
+    ServerError { status: u16, body: String },
+
+    /// An error that signifies that the Mojang API responded with a status code that this library doesn't expect.
+    #[error("mojang api returned unexpected status {status}: {body}")]
+    UnexpectedStatus { status: u16, body: String },
+
+    /// An error that signifies that the Mojang API returned a successful response that could not be parsed.
+    /// The source is the underlying `serde_json` error.
+    #[error("mojang api returned a malformed response")]
+    MalformedResponse(#[source] BoxError),
+
+    /// An error that signifies that a `textures` profile property could not be decoded.
+    /// The source is the underlying base64 or JSON error.
+    #[error("invalid textures property")]
+    InvalidTextures(#[source] BoxError),
+
+    /// An error that signifies that a profile property is unsigned, or that its signature doesn't match its value.
+    #[error("invalid profile property signature")]
+    InvalidSignature,
+
+    /// An error that signifies that a public key could not be loaded. The source is the underlying key parsing error.
+    #[error("invalid public key")]
+    InvalidKey(#[source] BoxError),
+
+    /// An error from the transport in use, meaning that the request never got a response.
+    #[error("transport error")]
+    Transport(#[source] BoxError),
+
+    /// An error from the reader or writer in use by the `protocol` functions.
+    /// The source is the underlying `std::io` error.
+    #[error("i/o error")]
+    Io(#[source] BoxError),
 }
 
-type Result<T> = std::result::Result<T, Error>;
+type Result<T> = core::result::Result<T, Error>;
 
 /// A struct that represents a UUID with an online format (UUID v4).
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
@@ -45,6 +167,25 @@ pub struct OnlineUuid(Uuid);
 pub struct OfflineUuid(Uuid);
 
 /// An enum that can represent both kinds of UUIDs.
+///
+/// It can be parsed from a hyphenated, undashed, braced or URN UUID, or from an SNBT int array (`[I; a, b, c, d]`),
+/// ignoring surrounding whitespace, and displays as a hyphenated UUID, or as an undashed one with the alternate flag (`{:#}`).
+///
+/// # Examples
+/// ```rust
+/// use uuid_mc::{Error, PlayerUuid};
+///
+/// let uuid: PlayerUuid = "069a79f444e94726a5befca90e38aaf5".parse().unwrap();
+/// assert!(uuid.is_online());
+/// assert_eq!(uuid.to_string(), "069a79f4-44e9-4726-a5be-fca90e38aaf5");
+/// assert_eq!(format!("{uuid:#}"), "069a79f444e94726a5befca90e38aaf5");
+///
+/// assert!(matches!("not a uuid".parse::<PlayerUuid>(), Err(Error::MalformedUuid)));
+/// assert!(matches!(
+///     "00000000-0000-0000-0000-000000000000".parse::<PlayerUuid>(),
+///     Err(Error::InvalidUuid)
+/// ));
+/// ```
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 #[serde(try_from = "Uuid")]
 #[serde(into = "Uuid")]
@@ -53,19 +194,13 @@ pub enum PlayerUuid {
     Offline(OfflineUuid),
 }
 
-#[cfg(feature = "online")]
-#[derive(Deserialize)]
-struct OnlineUuidResponse {
-    name: String,
-    id: PlayerUuid,
-}
-
 impl OnlineUuid {
     /// Uses the Mojang API to fetch the username belonging to this UUID.
     ///
     /// # Errors
-    /// If there is no user that corresponds to the provided UUID, an [`Error::InvalidUuid`] is returned.  
-    /// Otherwise, an [`Error::Transport`] can be returned in case of network failure.
+    /// If there is no user that corresponds to the provided UUID, an [`Error::NotFound`] is returned.  
+    /// If Mojang keeps rate limiting the request after all retries, an [`Error::RateLimited`] is returned.  
+    /// See [`Error`](enum@Error) for the other ways the request can fail.
     ///
     /// # Examples
     /// To fetch the user belonging to an arbitrary UUID, you can do:
@@ -84,21 +219,108 @@ impl OnlineUuid {
     /// ```
     #[cfg(feature = "online")]
     pub fn get_username(&self) -> Result<String> {
-        let response = ureq::get(&format!(
-            "https://sessionserver.mojang.com/session/minecraft/profile/{}",
-            self.0
-        ))
-        .call();
-
-        match response {
-            Ok(data) => {
-                let response: OnlineUuidResponse =
-                    data.into_json().map_err(|_| Error::MojangAPIError)?;
-                Ok(response.name)
-            }
-            Err(ureq::Error::Status(_, _)) => Err(Error::InvalidUsername),
-            Err(ureq::Error::Transport(x)) => Err(Error::Transport(x)),
-        }
+        self.get_username_with(&UreqTransport::default())
+    }
+
+    /// Same as [`get_username`](Self::get_username), but sends the request through the provided transport.
+    #[cfg(feature = "online")]
+    pub fn get_username_with(&self, transport: &impl HttpTransport) -> Result<String> {
+        MojangClient::with_transport(transport).get_username(self)
+    }
+
+    /// The `async` equivalent of [`get_username`](Self::get_username).
+    ///
+    /// # Errors
+    /// Identical to [`get_username`](Self::get_username).
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use uuid::Uuid;
+    /// use uuid_mc::PlayerUuid;
+    ///
+    /// # async fn run() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    /// let uuid = Uuid::try_parse("069a79f4-44e9-4726-a5be-fca90e38aaf5")?;
+    /// let player_uuid = PlayerUuid::new_with_uuid(uuid)?;
+    ///
+    /// let name = player_uuid.unwrap_online().get_username_async().await?;
+    /// assert_eq!(name, "Notch");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn get_username_async(&self) -> Result<String> {
+        self.get_username_async_with(&ReqwestTransport::default())
+            .await
+    }
+
+    /// Same as [`get_username_async`](Self::get_username_async), but sends the request through the provided transport.
+    #[cfg(feature = "async")]
+    pub async fn get_username_async_with(
+        &self,
+        transport: &(impl AsyncHttpTransport + Sync),
+    ) -> Result<String> {
+        MojangClient::with_transport(transport)
+            .get_username_async(self)
+            .await
+    }
+
+    /// Uses the Mojang session server to fetch the full profile belonging to this UUID,
+    /// including its properties (such as skins and capes).
+    ///
+    /// If `signed` is true, the properties are requested with `?unsigned=false`,
+    /// so that each of them carries the signature Mojang made for it.
+    ///
+    /// # Errors
+    /// If there is no user that corresponds to the provided UUID, an [`Error::NotFound`] is returned.  
+    /// See [`Error`](enum@Error) for the other ways the request can fail.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use uuid::Uuid;
+    /// use uuid_mc::PlayerUuid;
+    ///
+    /// # fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    /// let uuid = Uuid::try_parse("069a79f4-44e9-4726-a5be-fca90e38aaf5")?;
+    /// let player_uuid = PlayerUuid::new_with_uuid(uuid)?;
+    ///
+    /// let profile = player_uuid.unwrap_online().get_profile(true)?;
+    /// assert_eq!(profile.name, "Notch");
+    /// assert!(profile.property("textures").unwrap().signature.is_some());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "online")]
+    pub fn get_profile(&self, signed: bool) -> Result<GameProfile> {
+        self.get_profile_with(signed, &UreqTransport::default())
+    }
+
+    /// Same as [`get_profile`](Self::get_profile), but sends the request through the provided transport.
+    #[cfg(feature = "online")]
+    pub fn get_profile_with(
+        &self,
+        signed: bool,
+        transport: &impl HttpTransport,
+    ) -> Result<GameProfile> {
+        MojangClient::with_transport(transport).get_profile(self, signed)
+    }
+
+    /// The `async` equivalent of [`get_profile`](Self::get_profile).
+    #[cfg(feature = "async")]
+    pub async fn get_profile_async(&self, signed: bool) -> Result<GameProfile> {
+        self.get_profile_async_with(signed, &ReqwestTransport::default())
+            .await
+    }
+
+    /// Same as [`get_profile_async`](Self::get_profile_async), but sends the request through the provided transport.
+    #[cfg(feature = "async")]
+    pub async fn get_profile_async_with(
+        &self,
+        signed: bool,
+        transport: &(impl AsyncHttpTransport + Sync),
+    ) -> Result<GameProfile> {
+        MojangClient::with_transport(transport)
+            .get_profile_async(self, signed)
+            .await
     }
 
     /// Returns the inner [Uuid].
@@ -113,6 +335,27 @@ impl OnlineUuid {
 }
 
 impl OfflineUuid {
+    /// Creates the offline UUID of the given username. This can be evaluated at compile time;
+    /// see also [`offline_uuid!`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use uuid_mc::{OfflineUuid, PlayerUuid};
+    ///
+    /// const BOOLEAN_COERCION: OfflineUuid = OfflineUuid::new_with_username("boolean_coercion");
+    /// assert_eq!(
+    ///     PlayerUuid::new_with_offline_username("boolean_coercion"),
+    ///     PlayerUuid::Offline(BOOLEAN_COERCION)
+    /// );
+    /// ```
+    #[cfg(feature = "offline")]
+    pub const fn new_with_username(username: &str) -> Self {
+        Self(name_uuid_from_parts(&[
+            b"OfflinePlayer:",
+            username.as_bytes(),
+        ]))
+    }
+
     /// Returns the inner [Uuid].
     pub fn as_uuid(&self) -> &Uuid {
         &self.0
@@ -126,10 +369,13 @@ impl OfflineUuid {
 
 impl PlayerUuid {
     /// Creates a new instance using the username of an online player, by polling the Mojang API.
+    /// A [`Username`] can be passed as well, to validate the name before any request is sent.
     ///
     /// # Errors
-    /// If there is no user that corresponds to the provided username, an [`Error::InvalidUsername`] is returned.  
-    /// Otherwise, an [`Error::Transport`] can be returned in case of network failure.
+    /// If there is no user that corresponds to the provided username, an [`Error::NotFound`] is returned,
+    /// and if the username is malformed, an [`Error::BadRequest`] is returned.  
+    /// If Mojang keeps rate limiting the request after all retries, an [`Error::RateLimited`] is returned.  
+    /// See [`Error`](enum@Error) for the other ways the request can fail.
     ///
     /// # Examples
     /// To fetch the UUID of an online user:
@@ -146,24 +392,55 @@ impl PlayerUuid {
     /// # }
     #[cfg(feature = "online")]
     pub fn new_with_online_username(username: &str) -> Result<Self> {
-        let response = ureq::get(&format!(
-            "https://api.mojang.com/users/profiles/minecraft/{}",
-            username
-        ))
-        .call();
-
-        match response {
-            Ok(data) => {
-                let response: OnlineUuidResponse =
-                    data.into_json().map_err(|_| Error::MojangAPIError)?;
-                Ok(response.id)
-            }
-            Err(ureq::Error::Status(_, _)) => Err(Error::InvalidUsername),
-            Err(ureq::Error::Transport(x)) => Err(Error::Transport(x)),
-        }
+        Self::new_with_online_username_with(username, &UreqTransport::default())
+    }
+
+    /// Same as [`new_with_online_username`](Self::new_with_online_username), but sends the request through the provided transport.
+    #[cfg(feature = "online")]
+    pub fn new_with_online_username_with(
+        username: &str,
+        transport: &impl HttpTransport,
+    ) -> Result<Self> {
+        MojangClient::with_transport(transport).get_uuid(username)
+    }
+
+    /// The `async` equivalent of [`new_with_online_username`](Self::new_with_online_username).
+    ///
+    /// # Errors
+    /// Identical to [`new_with_online_username`](Self::new_with_online_username).
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use uuid::Uuid;
+    /// use uuid_mc::PlayerUuid;
+    ///
+    /// # async fn run() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    /// let uuid = PlayerUuid::new_with_online_username_async("Notch").await?;
+    /// let uuid = uuid.as_uuid();
+    /// let expected = Uuid::try_parse("069a79f4-44e9-4726-a5be-fca90e38aaf5")?;
+    /// assert_eq!(uuid, &expected);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn new_with_online_username_async(username: &str) -> Result<Self> {
+        Self::new_with_online_username_async_with(username, &ReqwestTransport::default()).await
+    }
+
+    /// Same as [`new_with_online_username_async`](Self::new_with_online_username_async), but sends the request
+    /// through the provided transport.
+    #[cfg(feature = "async")]
+    pub async fn new_with_online_username_async_with(
+        username: &str,
+        transport: &(impl AsyncHttpTransport + Sync),
+    ) -> Result<Self> {
+        MojangClient::with_transport(transport)
+            .get_uuid_async(username)
+            .await
     }
 
     /// Creates a new instance using the username of an offline player.
+    /// A [`Username`] can be passed as well; note that offline UUIDs depend on the name's capitalization.
     ///
     /// # Examples
     /// To fetch the UUID of an offline user:
@@ -179,13 +456,8 @@ impl PlayerUuid {
     /// # Ok(())
     /// # }
     #[cfg(feature = "offline")]
-    pub fn new_with_offline_username(username: &str) -> Self {
-        let mut hash = md5::compute(format!("OfflinePlayer:{}", username)).0;
-        hash[6] = hash[6] & 0x0f | 0x30; // uuid version 3
-        hash[8] = hash[8] & 0x3f | 0x80; // RFC4122 variant
-
-        let uuid = Uuid::from_bytes(hash);
-        Self::Offline(OfflineUuid(uuid))
+    pub const fn new_with_offline_username(username: &str) -> Self {
+        Self::Offline(OfflineUuid::new_with_username(username))
     }
 
     /// Creates a new instance using an already existing [`Uuid`].
@@ -230,7 +502,7 @@ impl PlayerUuid {
         self.as_uuid().as_bytes()
     }
 
-    /// Similar to [`Result::unwrap`](std::result::Result::unwrap), this function returns the inner [`OfflineUuid`].
+    /// Similar to [`Result::unwrap`], this function returns the inner [`OfflineUuid`].
     ///
     /// # Panics
     /// If the inner UUID is not an offline one.
@@ -241,7 +513,7 @@ impl PlayerUuid {
         }
     }
 
-    /// Similar to [`Result::unwrap`](std::result::Result::unwrap), this function returns the inner [`OnlineUuid`].
+    /// Similar to [`Result::unwrap`], this function returns the inner [`OnlineUuid`].
     ///
     /// # Panics
     /// If the inner UUID is not an online one.
@@ -279,10 +551,110 @@ impl PlayerUuid {
     }
 }
 
+/// Evaluates the [`OfflineUuid`] of a username at compile time.
+///
+/// The result is a constant, so it can initialize `const`s and `static`s; to match against it,
+/// bind it to a `const` first.
+///
+/// # Examples
+/// ```rust
+/// use uuid_mc::{offline_uuid, OfflineUuid, PlayerUuid};
+///
+/// const ADMIN: OfflineUuid = offline_uuid!("boolean_coercion");
+///
+/// let uuid = PlayerUuid::new_with_offline_username("boolean_coercion").unwrap_offline();
+/// match uuid {
+///     ADMIN => {}
+///     _ => unreachable!(),
+/// }
+/// assert_eq!(ADMIN.as_uuid().to_string(), "db62bdfb-eddc-3acc-a14e-c703aba52549");
+/// ```
+#[cfg(feature = "offline")]
+#[macro_export]
+macro_rules! offline_uuid {
+    ($username:expr) => {{
+        const UUID: $crate::OfflineUuid = $crate::OfflineUuid::new_with_username($username);
+        UUID
+    }};
+}
+
+/// Computes a name-based UUID exactly like Java's `UUID.nameUUIDFromBytes`: the MD5 digest of `name`,
+/// with the version (3) and variant bits forced, and without a namespace.
+///
+/// This is how offline player UUIDs are derived (from `"OfflinePlayer:<username>"`), and how plugins
+/// and proxies such as Citizens and Floodgate derive UUIDs from other strings. It can be used in `const` contexts.
+///
+/// # Examples
+/// ```rust
+/// use uuid::{uuid, Uuid};
+/// use uuid_mc::name_uuid_from_bytes;
+///
+/// const NOTCH: Uuid = name_uuid_from_bytes(b"OfflinePlayer:Notch");
+/// assert_eq!(NOTCH, uuid!("b50ad385-829d-3141-a216-7e7d7539ba7f"));
+/// ```
+#[cfg(feature = "offline")]
+pub const fn name_uuid_from_bytes(name: &[u8]) -> Uuid {
+    name_uuid_from_parts(&[name])
+}
+
+/// Same as [`name_uuid_from_bytes`], for a name made of several parts, which saves concatenating them.
+#[cfg(feature = "offline")]
+const fn name_uuid_from_parts(parts: &[&[u8]]) -> Uuid {
+    let mut hash = md5::digest(parts);
+    hash[6] = hash[6] & 0x0f | 0x30; // uuid version 3
+    hash[8] = hash[8] & 0x3f | 0x80; // RFC4122 variant
+
+    Uuid::from_bytes(hash)
+}
+
+/// Computes the server hash (the `serverId` sent to the session server) of an online-mode login.
+///
+/// Minecraft hashes the server ID string (usually empty), the shared secret and the server's DER-encoded
+/// public key with SHA-1, and formats the digest as a signed (two's complement) hexadecimal number,
+/// without leading zeros. This is the hash that [`MojangClient::has_joined`] and [`MojangClient::join`] expect.
+///
+/// # Examples
+/// ```rust
+/// use uuid_mc::server_hash;
+///
+/// assert_eq!(server_hash("Notch", &[], &[]), "4ed1f46bbe04bc756bcb17c0c7ce3e4632f06a48");
+/// assert_eq!(server_hash("jeb_", &[], &[]), "-7c9d5b0044c130109a5d7b5fb5c317c02b4e28c1");
+/// ```
+#[cfg(any(feature = "online", feature = "async"))]
+pub fn server_hash(server_id: &str, shared_secret: &[u8], public_key: &[u8]) -> String {
+    use sha1::{Digest, Sha1};
+
+    let mut digest: [u8; 20] = Sha1::new()
+        .chain_update(server_id)
+        .chain_update(shared_secret)
+        .chain_update(public_key)
+        .finalize()
+        .into();
+
+    let negative = digest[0] & 0x80 != 0;
+    if negative {
+        // two's complement: invert every bit and add one
+        let mut carry = true;
+        for byte in digest.iter_mut().rev() {
+            let (value, overflow) = (!*byte).overflowing_add(carry as u8);
+            *byte = value;
+            carry = overflow;
+        }
+    }
+
+    let hex: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+    let hex = hex.trim_start_matches('0');
+    if negative {
+        format!("-{}", hex)
+    } else {
+        hex.to_owned()
+    }
+}
+
 impl TryFrom<Uuid> for PlayerUuid {
     type Error = Error;
 
-    fn try_from(value: Uuid) -> std::result::Result<Self, Self::Error> {
+    fn try_from(value: Uuid) -> core::result::Result<Self, Self::Error> {
         Self::new_with_uuid(value)
     }
 }
@@ -290,7 +662,7 @@ impl TryFrom<Uuid> for PlayerUuid {
 impl TryFrom<Uuid> for OnlineUuid {
     type Error = Error;
 
-    fn try_from(value: Uuid) -> std::result::Result<Self, Self::Error> {
+    fn try_from(value: Uuid) -> core::result::Result<Self, Self::Error> {
         PlayerUuid::new_with_uuid(value)?
             .online()
             .ok_or(Error::InvalidUuid)
@@ -300,7 +672,7 @@ impl TryFrom<Uuid> for OnlineUuid {
 impl TryFrom<Uuid> for OfflineUuid {
     type Error = Error;
 
-    fn try_from(value: Uuid) -> std::result::Result<Self, Self::Error> {
+    fn try_from(value: Uuid) -> core::result::Result<Self, Self::Error> {
         PlayerUuid::new_with_uuid(value)?
             .offline()
             .ok_or(Error::InvalidUuid)
@@ -325,10 +697,202 @@ impl From<OfflineUuid> for Uuid {
     }
 }
 
+/// Parses a UUID in any of the formats that the `FromStr` implementations accept.
+fn parse_uuid(s: &str) -> Result<Uuid> {
+    let s = s.trim();
+    if s.starts_with('[') {
+        nbt::from_snbt(s)
+    } else {
+        Uuid::try_parse(s).map_err(|_| Error::MalformedUuid)
+    }
+}
+
+/// Formats a UUID hyphenated, or undashed (like the Mojang API) with the alternate flag (`{:#}`).
+fn fmt_uuid(uuid: &Uuid, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if f.alternate() {
+        fmt::Display::fmt(&uuid.simple(), f)
+    } else {
+        fmt::Display::fmt(&uuid.hyphenated(), f)
+    }
+}
+
+impl FromStr for PlayerUuid {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::new_with_uuid(parse_uuid(s)?)
+    }
+}
+
+impl FromStr for OnlineUuid {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::try_from(parse_uuid(s)?)
+    }
+}
+
+impl FromStr for OfflineUuid {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::try_from(parse_uuid(s)?)
+    }
+}
+
+impl fmt::Display for PlayerUuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_uuid(self.as_uuid(), f)
+    }
+}
+
+impl fmt::Display for OnlineUuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_uuid(self.as_uuid(), f)
+    }
+}
+
+impl fmt::Display for OfflineUuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_uuid(self.as_uuid(), f)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(any(feature = "online", feature = "async"))]
+    use std::{collections::VecDeque, sync::Mutex};
+
+    #[cfg(any(feature = "online", feature = "async"))]
+    use transport::{HttpRequest, HttpResponse, TransportError};
+
+    /// A transport that replays canned responses in order, and records the requests it was sent.
+    #[cfg(any(feature = "online", feature = "async"))]
+    #[derive(Default)]
+    pub(crate) struct MockTransport {
+        responses: Mutex<VecDeque<HttpResponse>>,
+        requests: Mutex<Vec<HttpRequest>>,
+    }
+
+    #[cfg(any(feature = "online", feature = "async"))]
+    impl MockTransport {
+        pub(crate) fn new() -> Self {
+            Self::default()
+        }
+
+        pub(crate) fn respond(self, status: u16, body: &str) -> Self {
+            self.responses.lock().unwrap().push_back(HttpResponse {
+                status,
+                headers: Vec::new(),
+                body: body.as_bytes().to_vec(),
+            });
+            self
+        }
+
+        pub(crate) fn requests(&self) -> Vec<HttpRequest> {
+            self.requests.lock().unwrap().clone()
+        }
+
+        fn next(&self, request: HttpRequest) -> std::result::Result<HttpResponse, TransportError> {
+            self.requests.lock().unwrap().push(request);
+            self.responses
+                .lock()
+                .unwrap()
+                .pop_front()
+                .ok_or_else(|| "unexpected request".into())
+        }
+    }
+
+    #[cfg(feature = "online")]
+    impl HttpTransport for MockTransport {
+        fn send(&self, request: HttpRequest) -> std::result::Result<HttpResponse, TransportError> {
+            self.next(request)
+        }
+    }
+
+    #[cfg(feature = "async")]
+    impl AsyncHttpTransport for MockTransport {
+        async fn send(
+            &self,
+            request: HttpRequest,
+        ) -> std::result::Result<HttpResponse, TransportError> {
+            self.next(request)
+        }
+    }
+
+    #[test]
+    fn uuid_classification() {
+        let online = Uuid::try_parse("069a79f4-44e9-4726-a5be-fca90e38aaf5").unwrap();
+        let offline = Uuid::try_parse("db62bdfb-eddc-3acc-a14e-c703aba52549").unwrap();
+
+        assert!(PlayerUuid::new_with_uuid(online).unwrap().is_online());
+        assert!(PlayerUuid::new_with_uuid(offline).unwrap().is_offline());
+        assert!(matches!(
+            PlayerUuid::new_with_uuid(Uuid::nil()),
+            Err(Error::InvalidUuid)
+        ));
+        assert!(OnlineUuid::try_from(offline).is_err());
+        assert!(OfflineUuid::try_from(offline).is_ok());
+    }
+
+    #[test]
+    fn error_sources() {
+        use core::error::Error as _;
+
+        let error = Error::Transport("connection reset".into());
+        assert_eq!(error.to_string(), "transport error");
+        assert_eq!(error.source().unwrap().to_string(), "connection reset");
+    }
+
+    #[test]
+    fn parsing_and_display() {
+        let notch: OnlineUuid = "069a79f4-44e9-4726-a5be-fca90e38aaf5".parse().unwrap();
+        for text in [
+            "069a79f444e94726a5befca90e38aaf5",
+            "{069a79f4-44e9-4726-a5be-fca90e38aaf5}",
+            "urn:uuid:069a79f4-44e9-4726-a5be-fca90e38aaf5",
+            "[I; 110787060, 1156138790, -1514210135, 238594805]",
+            " 069a79f4-44e9-4726-a5be-fca90e38aaf5\n",
+            "\t[I; 110787060, 1156138790, -1514210135, 238594805] ",
+        ] {
+            assert_eq!(text.parse::<OnlineUuid>().unwrap(), notch, "{text}");
+            assert_eq!(
+                text.parse::<PlayerUuid>().unwrap(),
+                PlayerUuid::Online(notch)
+            );
+        }
+
+        assert_eq!(notch.to_string(), "069a79f4-44e9-4726-a5be-fca90e38aaf5");
+        assert_eq!(format!("{notch:#}"), "069a79f444e94726a5befca90e38aaf5");
+        let offline: OfflineUuid = "db62bdfbeddc3acca14ec703aba52549".parse().unwrap();
+        assert_eq!(
+            PlayerUuid::Offline(offline).to_string(),
+            "db62bdfb-eddc-3acc-a14e-c703aba52549"
+        );
+
+        for malformed in [
+            "",
+            "069a79f4",
+            "069a79f4-44e9-4726-a5be-fca90e38aaf",
+            "[I; 1, 2]",
+        ] {
+            assert!(
+                matches!(malformed.parse::<PlayerUuid>(), Err(Error::MalformedUuid)),
+                "{malformed}"
+            );
+        }
+        assert!(matches!(
+            "069a79f4-44e9-4726-a5be-fca90e38aaf5".parse::<OfflineUuid>(),
+            Err(Error::InvalidUuid)
+        ));
+        assert!(matches!(
+            "[I; 0, 0, 0, 0]".parse::<PlayerUuid>(),
+            Err(Error::InvalidUuid)
+        ));
+    }
+
     #[cfg(feature = "offline")]
     #[test]
     fn offline_uuids() {
@@ -352,6 +916,42 @@ mod tests {
             .for_each(|(uuid1, uuid2)| assert_eq!(uuid1, uuid2));
     }
 
+    #[cfg(feature = "offline")]
+    #[test]
+    fn const_offline_uuids() {
+        const BOOL: OfflineUuid = offline_uuid!("bool");
+        static UPPER_BOOL: OfflineUuid = offline_uuid!("BOOL");
+
+        let classify = |uuid: OfflineUuid| match uuid {
+            BOOL => "bool",
+            _ if uuid == UPPER_BOOL => "BOOL",
+            _ => "other",
+        };
+        for name in ["bool", "BOOL", "BoOl"] {
+            let uuid = PlayerUuid::new_with_offline_username(name).unwrap_offline();
+            assert_eq!(classify(uuid), if name == "BoOl" { "other" } else { name });
+        }
+
+        assert_eq!(
+            BOOL.as_uuid(),
+            &Uuid::try_parse("e9fd750e-29c2-3d85-80c9-64618059d454").unwrap()
+        );
+    }
+
+    #[cfg(any(feature = "online", feature = "async"))]
+    #[test]
+    fn server_hashes() {
+        let values = vec![
+            ("Notch", "4ed1f46bbe04bc756bcb17c0c7ce3e4632f06a48"),
+            ("jeb_", "-7c9d5b0044c130109a5d7b5fb5c317c02b4e28c1"),
+            ("simon", "88e16a1019277b15d58faf0541e11910eb756f6"),
+        ];
+
+        values
+            .into_iter()
+            .for_each(|(server_id, hash)| assert_eq!(server_hash(server_id, &[], &[]), hash));
+    }
+
     #[cfg(feature = "online")]
     #[test]
     fn online_uuids() {
@@ -396,4 +996,110 @@ mod tests {
             })
             .for_each(|(name1, name2)| assert_eq!(name1, name2));
     }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn online_uuids_async() {
+        let values = vec![
+            ("Notch", "069a79f4-44e9-4726-a5be-fca90e38aaf5"),
+            ("Dinnerbone", "61699b2e-d327-4a01-9f1e-0ea8c3f06bc6"),
+        ];
+
+        for (username, uuid) in values {
+            let uuid = Uuid::try_parse(uuid).unwrap();
+            let player_uuid = PlayerUuid::new_with_online_username_async(username)
+                .await
+                .unwrap();
+            assert_eq!(player_uuid.as_uuid(), &uuid);
+
+            let name = player_uuid
+                .unwrap_online()
+                .get_username_async()
+                .await
+                .unwrap();
+            assert_eq!(name, username);
+        }
+    }
+
+    #[cfg(feature = "online")]
+    #[test]
+    fn online_lookups_with_transport() {
+        let found = MockTransport::new()
+            .respond(
+                200,
+                r#"{"id":"069a79f444e94726a5befca90e38aaf5","name":"Notch"}"#,
+            )
+            .respond(
+                200,
+                r#"{"id":"069a79f444e94726a5befca90e38aaf5","name":"Notch"}"#,
+            );
+        let uuid = PlayerUuid::new_with_online_username_with("notch", &found).unwrap();
+        assert_eq!(
+            uuid.as_uuid(),
+            &Uuid::try_parse("069a79f4-44e9-4726-a5be-fca90e38aaf5").unwrap()
+        );
+        assert_eq!(
+            uuid.unwrap_online().get_username_with(&found).unwrap(),
+            "Notch"
+        );
+
+        let not_found = MockTransport::new().respond(404, "");
+        assert!(matches!(
+            PlayerUuid::new_with_online_username_with("notch", &not_found),
+            Err(Error::NotFound)
+        ));
+
+        let bad_request = MockTransport::new().respond(400, "bad name");
+        assert!(matches!(
+            PlayerUuid::new_with_online_username_with("not ch", &bad_request),
+            Err(Error::BadRequest { body }) if body == "bad name"
+        ));
+
+        let rate_limited = MockTransport::new().respond(429, "");
+        assert!(matches!(
+            MojangClient::builder()
+                .retry_policy(RetryPolicy::NONE)
+                .build_with(&rate_limited)
+                .get_uuid("notch"),
+            Err(Error::RateLimited { .. })
+        ));
+
+        let server_error = MockTransport::new().respond(418, "teapot");
+        assert!(matches!(
+            PlayerUuid::new_with_online_username_with("notch", &server_error),
+            Err(Error::UnexpectedStatus { status: 418, .. })
+        ));
+
+        let garbage = MockTransport::new().respond(200, "not json");
+        let error = PlayerUuid::new_with_online_username_with("notch", &garbage).unwrap_err();
+        assert!(matches!(error, Error::MalformedResponse(_)));
+        assert!(std::error::Error::source(&error)
+            .unwrap()
+            .is::<serde_json::Error>());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn online_lookups_with_async_transport() {
+        let transport = MockTransport::new()
+            .respond(
+                200,
+                r#"{"id":"069a79f444e94726a5befca90e38aaf5","name":"Notch"}"#,
+            )
+            .respond(204, "");
+        let uuid = PlayerUuid::new_with_online_username_async_with("notch", &transport)
+            .await
+            .unwrap();
+        assert_eq!(
+            uuid.as_uuid(),
+            &Uuid::try_parse("069a79f4-44e9-4726-a5be-fca90e38aaf5").unwrap()
+        );
+        assert!(matches!(
+            uuid.unwrap_online()
+                .get_username_async_with(&transport)
+                .await,
+            Err(Error::NotFound)
+        ));
+        assert_eq!(transport.requests().len(), 2);
+    }
 }
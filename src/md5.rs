@@ -0,0 +1,163 @@
+//! A `const` implementation of MD5, so that offline UUIDs can be computed at compile time.
+//!
+//! MD5 is only used here because Java's `UUID.nameUUIDFromBytes` uses it; it is not used for anything
+//! security-related.
+
+/// The per-round shift amounts.
+const SHIFTS: [u32; 64] = [
+    7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, //
+    5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, //
+    4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, //
+    6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+];
+
+/// The per-round constants, `floor(abs(sin(i + 1)) * 2^32)`.
+const CONSTANTS: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+/// Computes the MD5 digest of the concatenation of `parts`.
+pub(crate) const fn digest(parts: &[&[u8]]) -> [u8; 16] {
+    let mut state = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+    let mut block = [0; 64];
+    let mut filled = 0;
+    let mut length: u64 = 0;
+
+    let mut i = 0;
+    while i < parts.len() {
+        let part = parts[i];
+        let mut j = 0;
+        while j < part.len() {
+            block[filled] = part[j];
+            filled += 1;
+            if filled == 64 {
+                state = compress(state, &block);
+                filled = 0;
+            }
+            j += 1;
+        }
+        length = length.wrapping_add(part.len() as u64);
+        i += 1;
+    }
+
+    block[filled] = 0x80;
+    filled += 1;
+    if filled > 56 {
+        while filled < 64 {
+            block[filled] = 0;
+            filled += 1;
+        }
+        state = compress(state, &block);
+        filled = 0;
+    }
+    while filled < 56 {
+        block[filled] = 0;
+        filled += 1;
+    }
+    let bits = length.wrapping_mul(8).to_le_bytes();
+    let mut i = 0;
+    while i < 8 {
+        block[56 + i] = bits[i];
+        i += 1;
+    }
+    state = compress(state, &block);
+
+    let mut output = [0; 16];
+    let mut i = 0;
+    while i < 16 {
+        output[i] = state[i / 4].to_le_bytes()[i % 4];
+        i += 1;
+    }
+
+    output
+}
+
+/// Processes a single 64-byte block.
+const fn compress(state: [u32; 4], block: &[u8; 64]) -> [u32; 4] {
+    let mut words = [0; 16];
+    let mut i = 0;
+    while i < 16 {
+        words[i] = u32::from_le_bytes([
+            block[i * 4],
+            block[i * 4 + 1],
+            block[i * 4 + 2],
+            block[i * 4 + 3],
+        ]);
+        i += 1;
+    }
+
+    let [mut a, mut b, mut c, mut d] = state;
+    let mut i = 0;
+    while i < 64 {
+        let (f, g) = match i / 16 {
+            0 => ((b & c) | (!b & d), i),
+            1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+            2 => (b ^ c ^ d, (3 * i + 5) % 16),
+            _ => (c ^ (b | !d), (7 * i) % 16),
+        };
+        let f = f
+            .wrapping_add(a)
+            .wrapping_add(CONSTANTS[i])
+            .wrapping_add(words[g]);
+        a = d;
+        d = c;
+        c = b;
+        b = b.wrapping_add(f.rotate_left(SHIFTS[i]));
+        i += 1;
+    }
+
+    [
+        state[0].wrapping_add(a),
+        state[1].wrapping_add(b),
+        state[2].wrapping_add(c),
+        state[3].wrapping_add(d),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(digest: [u8; 16]) -> String {
+        digest.iter().map(|x| format!("{x:02x}")).collect()
+    }
+
+    #[test]
+    fn rfc_1321_vectors() {
+        let values = [
+            ("", "d41d8cd98f00b204e9800998ecf8427e"),
+            ("a", "0cc175b9c0f1b6a831c399e269772661"),
+            ("abc", "900150983cd24fb0d6963f7d28e17f72"),
+            ("message digest", "f96b697d7cb7938d525a2f31aaf161d0"),
+            (
+                "abcdefghijklmnopqrstuvwxyz",
+                "c3fcd3d76192e4007dfb496cca67e13b",
+            ),
+            (
+                "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+                "d174ab98d277d9f5a5611c2c9f419d9f",
+            ),
+            (
+                "12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+                "57edf4a22be3c955ac49da2e2107b67a",
+            ),
+        ];
+
+        for (input, expected) in values {
+            assert_eq!(hex(digest(&[input.as_bytes()])), expected);
+        }
+
+        let (head, tail) = values[6].0.split_at(61);
+        assert_eq!(
+            hex(digest(&[head.as_bytes(), tail.as_bytes()])),
+            values[6].1
+        );
+    }
+}
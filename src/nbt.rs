@@ -0,0 +1,298 @@
+//! The NBT representation of UUIDs, used by Minecraft since 1.16: an int array of four big-endian `i32`s.
+//!
+//! In SNBT (the text form of NBT, as used by commands) such a UUID is written as `[I; a, b, c, d]`.
+//!
+//! Older worlds (and Java's `UUID.getMostSignificantBits`/`getLeastSignificantBits`, as used by many plugins)
+//! store UUIDs as two `i64`s instead, such as the `UUIDMost` and `UUIDLeast` tags.
+
+use alloc::format;
+use alloc::string::String;
+
+use crate::{Error, OfflineUuid, OnlineUuid, PlayerUuid, Result, Uuid};
+
+/// Splits a UUID into the four `i32`s of its NBT form, most significant first.
+pub(crate) fn to_int_array(uuid: &Uuid) -> [i32; 4] {
+    let bytes = uuid.as_bytes();
+    let mut array = [0; 4];
+    for (int, chunk) in array.iter_mut().zip(bytes.chunks_exact(4)) {
+        *int = i32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+
+    array
+}
+
+/// Joins the four `i32`s of a UUID's NBT form.
+pub(crate) fn from_int_array(array: [i32; 4]) -> Uuid {
+    let mut bytes = [0; 16];
+    for (chunk, int) in bytes.chunks_exact_mut(4).zip(array) {
+        chunk.copy_from_slice(&int.to_be_bytes());
+    }
+
+    Uuid::from_bytes(bytes)
+}
+
+pub(crate) fn to_most_least(uuid: &Uuid) -> (i64, i64) {
+    let (most, least) = uuid.as_u64_pair();
+    (most as i64, least as i64)
+}
+
+pub(crate) fn from_most_least(most: i64, least: i64) -> Uuid {
+    Uuid::from_u64_pair(most as u64, least as u64)
+}
+
+fn to_snbt(uuid: &Uuid) -> String {
+    let [a, b, c, d] = to_int_array(uuid);
+    format!("[I; {a}, {b}, {c}, {d}]")
+}
+
+/// Parses an SNBT int array of exactly four elements. Whitespace around the elements is allowed,
+/// like the game allows it.
+pub(crate) fn from_snbt(snbt: &str) -> Result<Uuid> {
+    let elements = snbt
+        .trim()
+        .strip_prefix('[')
+        .and_then(|x| x.strip_suffix(']'))
+        .and_then(|x| x.trim_start().strip_prefix("I;"))
+        .ok_or(Error::MalformedUuid)?;
+
+    let mut array = [0; 4];
+    let mut elements = elements.split(',');
+    for int in &mut array {
+        let element = elements.next().ok_or(Error::MalformedUuid)?;
+        *int = element.trim().parse().map_err(|_| Error::MalformedUuid)?;
+    }
+    if elements.next().is_some() {
+        return Err(Error::MalformedUuid);
+    }
+
+    Ok(from_int_array(array))
+}
+
+impl PlayerUuid {
+    /// Returns the UUID in its NBT form: four `i32`s, most significant first.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use uuid_mc::PlayerUuid;
+    ///
+    /// # fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    /// let uuid = PlayerUuid::new_with_uuid("069a79f4-44e9-4726-a5be-fca90e38aaf5".parse()?)?;
+    /// assert_eq!(uuid.to_int_array(), [110787060, 1156138790, -1514210135, 238594805]);
+    /// assert_eq!(uuid.to_snbt(), "[I; 110787060, 1156138790, -1514210135, 238594805]");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_int_array(&self) -> [i32; 4] {
+        to_int_array(self.as_uuid())
+    }
+
+    /// Creates a new instance from the NBT form of a UUID.
+    ///
+    /// # Errors
+    /// In case the UUID is neither offline (v3) or online (v4), an [`Error::InvalidUuid`] is returned.
+    pub fn from_int_array(array: [i32; 4]) -> Result<Self> {
+        Self::new_with_uuid(from_int_array(array))
+    }
+
+    /// Formats the UUID as an SNBT int array, `[I; a, b, c, d]`.
+    pub fn to_snbt(&self) -> String {
+        to_snbt(self.as_uuid())
+    }
+
+    /// Parses an SNBT int array, such as `[I; 110787060, 1156138790, -1514210135, 238594805]`.
+    ///
+    /// # Errors
+    /// If the text isn't an int array of four elements, an [`Error::MalformedUuid`] is returned,
+    /// and in case the UUID is neither offline (v3) or online (v4), an [`Error::InvalidUuid`] is returned.
+    pub fn from_snbt(snbt: &str) -> Result<Self> {
+        Self::new_with_uuid(from_snbt(snbt)?)
+    }
+
+    /// Returns the most and least significant 64 bits of the UUID, as signed integers,
+    /// which is how Java's `UUID` and legacy NBT (`UUIDMost` and `UUIDLeast`) store them.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use uuid_mc::PlayerUuid;
+    ///
+    /// # fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    /// let uuid = PlayerUuid::from_most_least(475826800676128550, -6503483008858150155)?;
+    /// assert_eq!(uuid.as_uuid().to_string(), "069a79f4-44e9-4726-a5be-fca90e38aaf5");
+    /// assert_eq!(uuid.to_most_least(), (475826800676128550, -6503483008858150155));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_most_least(&self) -> (i64, i64) {
+        to_most_least(self.as_uuid())
+    }
+
+    /// Creates a new instance from the most and least significant 64 bits of a UUID, as signed integers.
+    ///
+    /// # Errors
+    /// In case the UUID is neither offline (v3) or online (v4), an [`Error::InvalidUuid`] is returned.
+    pub fn from_most_least(most: i64, least: i64) -> Result<Self> {
+        Self::new_with_uuid(from_most_least(most, least))
+    }
+}
+
+impl OnlineUuid {
+    /// Returns the UUID in its NBT form. See [`PlayerUuid::to_int_array`].
+    pub fn to_int_array(&self) -> [i32; 4] {
+        to_int_array(self.as_uuid())
+    }
+
+    /// Creates a new instance from the NBT form of a UUID.
+    ///
+    /// # Errors
+    /// In case the UUID isn't online (v4), an [`Error::InvalidUuid`] is returned.
+    pub fn from_int_array(array: [i32; 4]) -> Result<Self> {
+        Self::try_from(from_int_array(array))
+    }
+
+    /// Formats the UUID as an SNBT int array. See [`PlayerUuid::to_snbt`].
+    pub fn to_snbt(&self) -> String {
+        to_snbt(self.as_uuid())
+    }
+
+    /// Parses an SNBT int array.
+    ///
+    /// # Errors
+    /// If the text isn't an int array of four elements, an [`Error::MalformedUuid`] is returned,
+    /// and in case the UUID isn't online (v4), an [`Error::InvalidUuid`] is returned.
+    pub fn from_snbt(snbt: &str) -> Result<Self> {
+        Self::try_from(from_snbt(snbt)?)
+    }
+
+    /// Returns the most and least significant 64 bits of the UUID, as signed integers. See [`PlayerUuid::to_most_least`].
+    pub fn to_most_least(&self) -> (i64, i64) {
+        to_most_least(self.as_uuid())
+    }
+
+    /// Creates a new instance from the most and least significant 64 bits of a UUID, as signed integers.
+    ///
+    /// # Errors
+    /// In case the UUID isn't online (v4), an [`Error::InvalidUuid`] is returned.
+    pub fn from_most_least(most: i64, least: i64) -> Result<Self> {
+        Self::try_from(from_most_least(most, least))
+    }
+}
+
+impl OfflineUuid {
+    /// Returns the UUID in its NBT form. See [`PlayerUuid::to_int_array`].
+    pub fn to_int_array(&self) -> [i32; 4] {
+        to_int_array(self.as_uuid())
+    }
+
+    /// Creates a new instance from the NBT form of a UUID.
+    ///
+    /// # Errors
+    /// In case the UUID isn't offline (v3), an [`Error::InvalidUuid`] is returned.
+    pub fn from_int_array(array: [i32; 4]) -> Result<Self> {
+        Self::try_from(from_int_array(array))
+    }
+
+    /// Formats the UUID as an SNBT int array. See [`PlayerUuid::to_snbt`].
+    pub fn to_snbt(&self) -> String {
+        to_snbt(self.as_uuid())
+    }
+
+    /// Parses an SNBT int array.
+    ///
+    /// # Errors
+    /// If the text isn't an int array of four elements, an [`Error::MalformedUuid`] is returned,
+    /// and in case the UUID isn't offline (v3), an [`Error::InvalidUuid`] is returned.
+    pub fn from_snbt(snbt: &str) -> Result<Self> {
+        Self::try_from(from_snbt(snbt)?)
+    }
+
+    /// Returns the most and least significant 64 bits of the UUID, as signed integers. See [`PlayerUuid::to_most_least`].
+    pub fn to_most_least(&self) -> (i64, i64) {
+        to_most_least(self.as_uuid())
+    }
+
+    /// Creates a new instance from the most and least significant 64 bits of a UUID, as signed integers.
+    ///
+    /// # Errors
+    /// In case the UUID isn't offline (v3), an [`Error::InvalidUuid`] is returned.
+    pub fn from_most_least(most: i64, least: i64) -> Result<Self> {
+        Self::try_from(from_most_least(most, least))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTCH: [i32; 4] = [110787060, 1156138790, -1514210135, 238594805];
+
+    #[test]
+    fn int_arrays() {
+        let uuid = OnlineUuid::from_int_array(NOTCH).unwrap();
+        assert_eq!(
+            uuid.as_uuid(),
+            &Uuid::try_parse("069a79f4-44e9-4726-a5be-fca90e38aaf5").unwrap()
+        );
+        assert_eq!(uuid.to_int_array(), NOTCH);
+        assert!(PlayerUuid::from_int_array(NOTCH).unwrap().is_online());
+        assert!(matches!(
+            OfflineUuid::from_int_array(NOTCH),
+            Err(Error::InvalidUuid)
+        ));
+        assert!(matches!(
+            PlayerUuid::from_int_array([0; 4]),
+            Err(Error::InvalidUuid)
+        ));
+    }
+
+    #[test]
+    fn most_least() {
+        let uuid = OnlineUuid::from_int_array(NOTCH).unwrap();
+        let (most, least) = uuid.to_most_least();
+        assert_eq!((most, least), (475826800676128550, -6503483008858150155));
+        assert_eq!(OnlineUuid::from_most_least(most, least).unwrap(), uuid);
+        assert!(matches!(
+            OfflineUuid::from_most_least(most, least),
+            Err(Error::InvalidUuid)
+        ));
+
+        let offline =
+            PlayerUuid::from_most_least(-2638337541960615220, -6823297566841166519).unwrap();
+        assert_eq!(
+            offline.as_uuid(),
+            &Uuid::try_parse("db62bdfb-eddc-3acc-a14e-c703aba52549").unwrap()
+        );
+        assert!(offline.is_offline());
+    }
+
+    #[test]
+    fn snbt() {
+        let uuid = OnlineUuid::from_int_array(NOTCH).unwrap();
+        let snbt = uuid.to_snbt();
+        assert_eq!(snbt, "[I; 110787060, 1156138790, -1514210135, 238594805]");
+        assert_eq!(OnlineUuid::from_snbt(&snbt).unwrap(), uuid);
+        assert_eq!(
+            PlayerUuid::from_snbt("[I;110787060,1156138790,-1514210135,238594805]").unwrap(),
+            PlayerUuid::Online(uuid)
+        );
+        assert_eq!(
+            OnlineUuid::from_snbt(" [ I; 110787060 , 1156138790,\n-1514210135, 238594805 ] ")
+                .unwrap(),
+            uuid
+        );
+
+        for malformed in [
+            "",
+            "[I;]",
+            "[110787060, 1156138790, -1514210135, 238594805]",
+            "[L; 110787060, 1156138790, -1514210135, 238594805]",
+            "[I; 110787060, 1156138790, -1514210135]",
+            "[I; 110787060, 1156138790, -1514210135, 238594805, 0]",
+            "[I; 110787060, 1156138790, -1514210135, 2385948050]",
+        ] {
+            assert!(
+                matches!(PlayerUuid::from_snbt(malformed), Err(Error::MalformedUuid)),
+                "{malformed}"
+            );
+        }
+    }
+}
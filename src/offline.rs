@@ -0,0 +1,113 @@
+//! Reconciliation of offline UUIDs across the capitalizations of a username.
+//!
+//! Offline UUIDs are derived from the exact bytes of a username, so `BOOL` and `bool` get different UUIDs,
+//! while many offline servers treat them as the same player. The helpers in this module find the UUIDs
+//! that such a player may have been split into.
+//!
+//! Names are case-folded with ASCII lowercasing, the same rule [`Username`](crate::Username) comparisons and
+//! `MojangClient::get_uuids` use; other characters have to match exactly.
+
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{OfflineUuid, PlayerUuid};
+
+/// Returns the names in `seen` that match `username` case-insensitively, each with its offline UUID.
+///
+/// Names are returned in the order they were seen, without duplicates.
+///
+/// # Examples
+/// ```rust
+/// use uuid_mc::offline::case_variants;
+///
+/// let seen = ["bool", "Notch", "BOOL", "bool"];
+/// let variants = case_variants("Bool", seen);
+///
+/// let names: Vec<_> = variants.iter().map(|(name, _)| name.as_str()).collect();
+/// assert_eq!(names, ["bool", "BOOL"]);
+/// assert_ne!(variants[0].1, variants[1].1);
+/// ```
+pub fn case_variants<I, S>(username: &str, seen: I) -> Vec<(String, OfflineUuid)>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut variants: Vec<(String, OfflineUuid)> = Vec::new();
+    for name in seen {
+        let name = name.as_ref();
+        if !name.eq_ignore_ascii_case(username) || variants.iter().any(|(x, _)| x == name) {
+            continue;
+        }
+
+        let uuid = PlayerUuid::new_with_offline_username(name).unwrap_offline();
+        variants.push((name.to_owned(), uuid));
+    }
+
+    variants
+}
+
+/// Groups `(username, OfflineUuid)` pairs by case-folded username.
+///
+/// Every group holds the distinct pairs of one player, in the order they were given;
+/// a group with more than one UUID is a player whose data has been split between capitalizations.
+/// The groups are keyed by the case-folded username.
+///
+/// # Examples
+/// ```rust
+/// use uuid_mc::offline::group_by_name;
+/// use uuid_mc::PlayerUuid;
+///
+/// let pairs = ["bool", "BOOL", "Notch"]
+///     .map(|name| (name, PlayerUuid::new_with_offline_username(name).unwrap_offline()));
+/// let groups = group_by_name(pairs);
+///
+/// assert_eq!(groups["bool"].len(), 2);
+/// assert_eq!(groups["notch"].len(), 1);
+/// ```
+pub fn group_by_name<I, S>(pairs: I) -> BTreeMap<String, Vec<(String, OfflineUuid)>>
+where
+    I: IntoIterator<Item = (S, OfflineUuid)>,
+    S: Into<String>,
+{
+    let mut groups: BTreeMap<String, Vec<(String, OfflineUuid)>> = BTreeMap::new();
+    for (name, uuid) in pairs {
+        let name = name.into();
+        let group = groups.entry(name.to_ascii_lowercase()).or_default();
+        if !group.iter().any(|(x, y)| *x == name && *y == uuid) {
+            group.push((name, uuid));
+        }
+    }
+
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reconciliation() {
+        let seen = ["BoOl", "bool", "Notch", "BOOL", "BoOl"];
+
+        let variants = case_variants("bool", seen);
+        assert_eq!(
+            variants
+                .iter()
+                .map(|(_, uuid)| uuid.as_uuid().to_string())
+                .collect::<Vec<_>>(),
+            [
+                "e38f2cf4-72d2-3a84-8278-fed6908d2746",
+                "e9fd750e-29c2-3d85-80c9-64618059d454",
+                "c5d06acf-0ef6-3a68-bf0b-b57806bcbef5",
+            ]
+        );
+        assert!(case_variants("jeb_", seen).is_empty());
+        assert!(case_variants("ñandú", ["ÑANDÚ"]).is_empty());
+
+        let groups = group_by_name(variants.iter().cloned().chain(variants.clone()));
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups["bool"], variants);
+    }
+}
@@ -0,0 +1,1133 @@
+//! A configurable client for Mojang's (or a compatible) API.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::retry::{self, RateLimit, RetryPolicy, TokenBucket};
+#[cfg(feature = "async")]
+use crate::transport::AsyncHttpTransport;
+#[cfg(feature = "online")]
+use crate::transport::HttpTransport;
+use crate::transport::{DefaultTransport, HttpRequest, HttpResponse};
+use crate::{Error, GameProfile, OnlineUuid, PlayerUuid, Result, Username};
+
+/// The default base URL of the Mojang API, which resolves usernames to UUIDs.
+pub const MOJANG_API_URL: &str = "https://api.mojang.com";
+
+/// The default base URL of the Mojang session server, which resolves UUIDs to profiles.
+pub const MOJANG_SESSION_SERVER_URL: &str = "https://sessionserver.mojang.com";
+
+/// The default base URL of the Minecraft services API, which requires a Minecraft access token.
+pub const MINECRAFT_SERVICES_URL: &str = "https://api.minecraftservices.com";
+
+/// The maximum number of usernames Mojang accepts in a single bulk lookup request.
+pub const BULK_LOOKUP_LIMIT: usize = 10;
+
+/// A player's UUID together with the canonical capitalization of their username.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ProfileSummary {
+    pub id: OnlineUuid,
+    pub name: String,
+}
+
+/// Whether a username can be taken, as reported by the Minecraft services API.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NameAvailability {
+    /// Nobody has the name, and it may be used.
+    Available,
+    /// Another player already has the name.
+    Duplicate,
+    /// The name is blocked by Mojang.
+    NotAllowed,
+}
+
+impl NameAvailability {
+    /// Returns whether the name may be used.
+    pub fn is_available(&self) -> bool {
+        *self == Self::Available
+    }
+}
+
+#[derive(Deserialize)]
+struct UuidResponse {
+    id: PlayerUuid,
+}
+
+#[derive(Deserialize)]
+struct NameAvailabilityResponse {
+    status: NameAvailability,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JoinRequest<'a> {
+    access_token: &'a str,
+    selected_profile: uuid::fmt::Simple,
+    server_id: &'a str,
+}
+
+/// The error body returned by Yggdrasil endpoints.
+#[derive(Deserialize)]
+struct YggdrasilError {
+    error: String,
+}
+
+/// A client for the Mojang API, holding the base URLs and request settings that every call uses.
+///
+/// The free functions such as [`PlayerUuid::new_with_online_username`] use a default client;
+/// build one yourself to talk to a mock server, a caching proxy or a Yggdrasil-compatible server.
+///
+/// By default, a client limits itself to [`RateLimit::MOJANG`] and retries rate limited and failed requests
+/// according to the default [`RetryPolicy`]. Clones of a client share the same rate limit, and so do all
+/// the clients that use the default limit with the official API URLs (including the one behind the free functions),
+/// since Mojang applies its limit per IP address.
+///
+/// # Examples
+/// To point the client at an authlib-injector compatible server:
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use uuid_mc::MojangClient;
+///
+/// # fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+/// let client = MojangClient::builder()
+///     .authlib_injector("https://example.com/api/yggdrasil")
+///     .timeout(Duration::from_secs(5))
+///     .user_agent("my-proxy/1.0")
+///     .build();
+///
+/// let uuid = client.get_uuid("Notch")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MojangClient<T = DefaultTransport> {
+    transport: T,
+    api_url: String,
+    session_server_url: String,
+    services_url: String,
+    timeout: Option<Duration>,
+    user_agent: Option<String>,
+    rate_limiter: Option<Arc<TokenBucket>>,
+    retry_policy: RetryPolicy,
+}
+
+/// A builder for [`MojangClient`], created with [`MojangClient::builder`].
+#[derive(Clone, Debug)]
+pub struct MojangClientBuilder {
+    api_url: String,
+    session_server_url: String,
+    services_url: String,
+    timeout: Option<Duration>,
+    user_agent: Option<String>,
+    rate_limit: Option<RateLimit>,
+    retry_policy: RetryPolicy,
+}
+
+impl MojangClient {
+    /// Creates a client for the official Mojang API, using the default transport.
+    pub fn new() -> Self {
+        Self::builder().build()
+    }
+
+    /// Creates a builder with the official Mojang API URLs and no timeout or User-Agent set.
+    pub fn builder() -> MojangClientBuilder {
+        MojangClientBuilder::default()
+    }
+}
+
+impl Default for MojangClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> MojangClient<T> {
+    /// Creates a client for the official Mojang API, using the provided transport.
+    pub fn with_transport(transport: T) -> Self {
+        MojangClient::builder().build_with(transport)
+    }
+
+    /// Returns the transport this client sends its requests through.
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Returns the base URL of the Minecraft Services API this client uses.
+    pub fn services_url(&self) -> &str {
+        &self.services_url
+    }
+
+    pub(crate) fn request(&self, url: String) -> HttpRequest {
+        self.configure(HttpRequest::get(url))
+    }
+
+    pub(crate) fn configure(&self, mut request: HttpRequest) -> HttpRequest {
+        request.timeout = self.timeout;
+        if let Some(user_agent) = &self.user_agent {
+            request
+                .headers
+                .push(("User-Agent".to_owned(), user_agent.clone()));
+        }
+
+        request
+    }
+
+    fn uuid_request(&self, username: &str) -> HttpRequest {
+        self.request(format!(
+            "{}/users/profiles/minecraft/{}",
+            self.api_url,
+            encode_query(username)
+        ))
+    }
+
+    fn profile_request(&self, uuid: &OnlineUuid, signed: bool) -> HttpRequest {
+        let mut url = format!(
+            "{}/session/minecraft/profile/{}",
+            self.session_server_url,
+            uuid.as_uuid().simple()
+        );
+        if signed {
+            url.push_str("?unsigned=false");
+        }
+
+        self.request(url)
+    }
+
+    fn has_joined_request(
+        &self,
+        username: &str,
+        server_id: &str,
+        ip: Option<IpAddr>,
+    ) -> HttpRequest {
+        let mut url = format!(
+            "{}/session/minecraft/hasJoined?username={}&serverId={}",
+            self.session_server_url,
+            encode_query(username),
+            encode_query(server_id)
+        );
+        if let Some(ip) = ip {
+            write!(url, "&ip={}", encode_query(&ip.to_string())).unwrap();
+        }
+
+        self.request(url)
+    }
+
+    pub(crate) fn post_json(&self, url: String, body: &impl Serialize) -> HttpRequest {
+        let body = serde_json::to_vec(body).expect("request bodies always serialize");
+        self.configure(HttpRequest::post_json(url, body))
+    }
+
+    #[cfg(feature = "auth")]
+    pub(crate) fn post_form(&self, url: String, fields: &[(&str, &str)]) -> HttpRequest {
+        let body = fields
+            .iter()
+            .map(|(name, value)| format!("{}={}", encode_query(name), encode_query(value)))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        self.configure(HttpRequest::post_form(url, body.into_bytes()))
+    }
+
+    fn name_availability_request(&self, access_token: &str, name: &str) -> HttpRequest {
+        self.request(format!(
+            "{}/minecraft/profile/name/{}/available",
+            self.services_url,
+            encode_query(name)
+        ))
+        .header("Authorization", format!("Bearer {access_token}"))
+    }
+
+    fn bulk_requests(&self, lookup: &BulkLookup) -> Vec<HttpRequest> {
+        let url = format!("{}/profiles/minecraft", self.api_url);
+        lookup
+            .unique
+            .chunks(BULK_LOOKUP_LIMIT)
+            .map(|chunk| self.post_json(url.clone(), &chunk))
+            .collect()
+    }
+
+    fn join_request(
+        &self,
+        access_token: &str,
+        profile: &OnlineUuid,
+        server_id: &str,
+    ) -> HttpRequest {
+        let body = JoinRequest {
+            access_token,
+            selected_profile: profile.as_uuid().simple(),
+            server_id,
+        };
+        self.post_json(
+            format!("{}/session/minecraft/join", self.session_server_url),
+            &body,
+        )
+    }
+
+    fn rate_limit_delay(&self) -> Duration {
+        self.rate_limiter
+            .as_ref()
+            .map_or(Duration::ZERO, |bucket| bucket.acquire())
+    }
+
+    #[cfg(feature = "online")]
+    pub(crate) fn send(&self, request: HttpRequest) -> Result<HttpResponse>
+    where
+        T: HttpTransport,
+    {
+        let mut attempt = 0;
+        loop {
+            std::thread::sleep(self.rate_limit_delay());
+            let response = self
+                .transport
+                .send(request.clone())
+                .map_err(Error::Transport)?;
+
+            match self.retry_policy.delay(&response, attempt) {
+                Some(delay) => std::thread::sleep(delay),
+                None => return Ok(response),
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Sends a request to a host other than Mojang's, without the rate limit and retries that only apply to Mojang.
+    #[cfg(feature = "auth")]
+    pub(crate) fn send_external(&self, request: HttpRequest) -> Result<HttpResponse>
+    where
+        T: HttpTransport,
+    {
+        self.transport.send(request).map_err(Error::Transport)
+    }
+
+    /// The `async` equivalent of [`send_external`](Self::send_external).
+    #[cfg(all(feature = "auth", feature = "async"))]
+    pub(crate) async fn send_external_async(&self, request: HttpRequest) -> Result<HttpResponse>
+    where
+        T: AsyncHttpTransport,
+    {
+        self.transport.send(request).await.map_err(Error::Transport)
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) async fn send_async(&self, request: HttpRequest) -> Result<HttpResponse>
+    where
+        T: AsyncHttpTransport,
+    {
+        let mut attempt = 0;
+        loop {
+            sleep_async(self.rate_limit_delay()).await;
+            let response = self
+                .transport
+                .send(request.clone())
+                .await
+                .map_err(Error::Transport)?;
+
+            match self.retry_policy.delay(&response, attempt) {
+                Some(delay) => sleep_async(delay).await,
+                None => return Ok(response),
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Fetches the UUID of the online player with the given username.
+    ///
+    /// # Errors
+    /// See [`PlayerUuid::new_with_online_username`].
+    #[cfg(feature = "online")]
+    pub fn get_uuid(&self, username: &str) -> Result<PlayerUuid>
+    where
+        T: HttpTransport,
+    {
+        let response = self.send(self.uuid_request(username))?;
+        Ok(parse_json::<UuidResponse>(response)?.id)
+    }
+
+    /// The `async` equivalent of [`get_uuid`](Self::get_uuid).
+    #[cfg(feature = "async")]
+    pub async fn get_uuid_async(&self, username: &str) -> Result<PlayerUuid>
+    where
+        T: AsyncHttpTransport,
+    {
+        let response = self.send_async(self.uuid_request(username)).await?;
+        Ok(parse_json::<UuidResponse>(response)?.id)
+    }
+
+    /// Fetches the username belonging to the given UUID.
+    ///
+    /// # Errors
+    /// See [`OnlineUuid::get_username`].
+    #[cfg(feature = "online")]
+    pub fn get_username(&self, uuid: &OnlineUuid) -> Result<String>
+    where
+        T: HttpTransport,
+    {
+        Ok(self.get_profile(uuid, false)?.name)
+    }
+
+    /// The `async` equivalent of [`get_username`](Self::get_username).
+    #[cfg(feature = "async")]
+    pub async fn get_username_async(&self, uuid: &OnlineUuid) -> Result<String>
+    where
+        T: AsyncHttpTransport,
+    {
+        Ok(self.get_profile_async(uuid, false).await?.name)
+    }
+
+    /// Fetches the full profile belonging to the given UUID from the session server.
+    /// If `signed` is true, the profile properties are requested with their signatures.
+    ///
+    /// # Errors
+    /// See [`OnlineUuid::get_profile`].
+    #[cfg(feature = "online")]
+    pub fn get_profile(&self, uuid: &OnlineUuid, signed: bool) -> Result<GameProfile>
+    where
+        T: HttpTransport,
+    {
+        parse_json(self.send(self.profile_request(uuid, signed))?)
+    }
+
+    /// The `async` equivalent of [`get_profile`](Self::get_profile).
+    #[cfg(feature = "async")]
+    pub async fn get_profile_async(&self, uuid: &OnlineUuid, signed: bool) -> Result<GameProfile>
+    where
+        T: AsyncHttpTransport,
+    {
+        parse_json(self.send_async(self.profile_request(uuid, signed)).await?)
+    }
+
+    /// Checks with the session server whether the player with the given username has joined the server
+    /// identified by `server_id`, which is the hash computed during the login handshake.
+    ///
+    /// This is the check every online-mode server performs when a player logs in. If `ip` is provided,
+    /// the session server also checks that the player authenticated from that address.
+    ///
+    /// Returns the player's authenticated profile (with signed properties), or [`None`] if the player
+    /// hasn't authenticated with the session server, in which case they must be disconnected.
+    ///
+    /// # Errors
+    /// See [`Error`](enum@Error) for the ways the request can fail.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use uuid_mc::MojangClient;
+    ///
+    /// # fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    /// # let server_hash = String::new();
+    /// let client = MojangClient::new();
+    /// match client.has_joined("Notch", &server_hash, None)? {
+    ///     Some(profile) => println!("{} ({}) logged in", profile.name, profile.id.as_uuid()),
+    ///     None => println!("failed to verify username"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "online")]
+    pub fn has_joined(
+        &self,
+        username: &str,
+        server_id: &str,
+        ip: Option<IpAddr>,
+    ) -> Result<Option<GameProfile>>
+    where
+        T: HttpTransport,
+    {
+        let request = self.has_joined_request(username, server_id, ip);
+        parse_optional_json(self.send(request)?)
+    }
+
+    /// The `async` equivalent of [`has_joined`](Self::has_joined).
+    #[cfg(feature = "async")]
+    pub async fn has_joined_async(
+        &self,
+        username: &str,
+        server_id: &str,
+        ip: Option<IpAddr>,
+    ) -> Result<Option<GameProfile>>
+    where
+        T: AsyncHttpTransport,
+    {
+        let request = self.has_joined_request(username, server_id, ip);
+        parse_optional_json(self.send_async(request).await?)
+    }
+
+    /// Tells the session server that the player owning `access_token` is joining the server identified by
+    /// `server_id`, which is the hash computed during the login handshake (see [`server_hash`](crate::server_hash)).
+    ///
+    /// This is what the vanilla client does before sending Encryption Response; the server then confirms
+    /// the join with [`has_joined`](Self::has_joined). `profile` is the UUID of the player's selected profile.
+    ///
+    /// # Errors
+    /// If the access token is invalid or expired, an [`Error::InvalidToken`] is returned.  
+    /// See [`Error`](enum@Error) for the other ways the request can fail.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use uuid_mc::{server_hash, MojangClient, PlayerUuid};
+    ///
+    /// # fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    /// # let (access_token, shared_secret, public_key) = ("", [0; 16], []);
+    /// let profile = PlayerUuid::new_with_online_username("Notch")?.unwrap_online();
+    /// let hash = server_hash("", &shared_secret, &public_key);
+    /// MojangClient::new().join(access_token, &profile, &hash)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "online")]
+    pub fn join(&self, access_token: &str, profile: &OnlineUuid, server_id: &str) -> Result<()>
+    where
+        T: HttpTransport,
+    {
+        let request = self.join_request(access_token, profile, server_id);
+        check_join_status(self.send(request)?)
+    }
+
+    /// The `async` equivalent of [`join`](Self::join).
+    #[cfg(feature = "async")]
+    pub async fn join_async(
+        &self,
+        access_token: &str,
+        profile: &OnlineUuid,
+        server_id: &str,
+    ) -> Result<()>
+    where
+        T: AsyncHttpTransport,
+    {
+        let request = self.join_request(access_token, profile, server_id);
+        check_join_status(self.send_async(request).await?)
+    }
+
+    /// Checks whether a username can be taken, using the Minecraft services API.
+    ///
+    /// Mojang only answers this for authenticated accounts, so a Minecraft access token is required.
+    ///
+    /// # Errors
+    /// If the access token is invalid or expired, an [`Error::InvalidToken`] is returned.  
+    /// See [`Error`](enum@Error) for the other ways the request can fail.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use uuid_mc::{MojangClient, NameAvailability};
+    ///
+    /// # fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    /// # let access_token = "";
+    /// let availability = MojangClient::new().name_availability(access_token, "Notch")?;
+    /// assert_eq!(availability, NameAvailability::Duplicate);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "online")]
+    pub fn name_availability(&self, access_token: &str, name: &str) -> Result<NameAvailability>
+    where
+        T: HttpTransport,
+    {
+        let request = self.name_availability_request(access_token, name);
+        parse_json(self.send(request)?).map(|x: NameAvailabilityResponse| x.status)
+    }
+
+    /// The `async` equivalent of [`name_availability`](Self::name_availability).
+    #[cfg(feature = "async")]
+    pub async fn name_availability_async(
+        &self,
+        access_token: &str,
+        name: &str,
+    ) -> Result<NameAvailability>
+    where
+        T: AsyncHttpTransport,
+    {
+        let request = self.name_availability_request(access_token, name);
+        parse_json(self.send_async(request).await?).map(|x: NameAvailabilityResponse| x.status)
+    }
+
+    /// Fetches the UUIDs of many online players at once, using Mojang's bulk lookup endpoint.
+    ///
+    /// The usernames are deduplicated case-insensitively (folding ASCII letters only, like [`Username`] comparisons)
+    /// and sent in batches of [`BULK_LOOKUP_LIMIT`].
+    /// The returned map contains every requested username as a key (with its original capitalization),
+    /// mapped to the player's profile summary, or [`None`] if there is no such player.
+    ///
+    /// Usernames that can't belong to any player (those that [`Username::new_lenient`] rejects, such as empty
+    /// or overly long ones) are not sent, since Mojang would reject their whole batch; they map to [`None`].
+    ///
+    /// # Errors
+    /// Usernames that don't belong to any player are not an error.  
+    /// See [`Error`](enum@Error) for the ways the request can fail.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use uuid_mc::MojangClient;
+    ///
+    /// # fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    /// let client = MojangClient::new();
+    /// let profiles = client.get_uuids(["notch", "Dinnerbone", "NOTCH"])?;
+    ///
+    /// let notch = profiles["notch"].as_ref().unwrap();
+    /// assert_eq!(notch.name, "Notch");
+    /// assert_eq!(profiles["NOTCH"].as_ref(), Some(notch));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "online")]
+    pub fn get_uuids<I, S>(&self, usernames: I) -> Result<HashMap<String, Option<ProfileSummary>>>
+    where
+        T: HttpTransport,
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let lookup = BulkLookup::new(usernames);
+        let mut found = Vec::new();
+        for request in self.bulk_requests(&lookup) {
+            found.extend(parse_json::<Vec<ProfileSummary>>(self.send(request)?)?);
+        }
+
+        Ok(lookup.finish(found))
+    }
+
+    /// The `async` equivalent of [`get_uuids`](Self::get_uuids).
+    #[cfg(feature = "async")]
+    pub async fn get_uuids_async<I, S>(
+        &self,
+        usernames: I,
+    ) -> Result<HashMap<String, Option<ProfileSummary>>>
+    where
+        T: AsyncHttpTransport,
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let lookup = BulkLookup::new(usernames);
+        let mut found = Vec::new();
+        for request in self.bulk_requests(&lookup) {
+            found.extend(parse_json::<Vec<ProfileSummary>>(
+                self.send_async(request).await?,
+            )?);
+        }
+
+        Ok(lookup.finish(found))
+    }
+}
+
+impl MojangClientBuilder {
+    /// Sets the base URL of the API used to resolve usernames to UUIDs,
+    /// `https://api.mojang.com` by default.
+    pub fn api_url(mut self, url: impl Into<String>) -> Self {
+        self.api_url = trim_url(url.into());
+        self
+    }
+
+    /// Sets the base URL of the session server used to resolve UUIDs to profiles,
+    /// `https://sessionserver.mojang.com` by default.
+    pub fn session_server_url(mut self, url: impl Into<String>) -> Self {
+        self.session_server_url = trim_url(url.into());
+        self
+    }
+
+    /// Sets both base URLs for an authlib-injector compatible Yggdrasil server, given its API root.
+    pub fn authlib_injector(self, root: impl Into<String>) -> Self {
+        let root = trim_url(root.into());
+        self.api_url(format!("{root}/api"))
+            .session_server_url(format!("{root}/sessionserver"))
+    }
+
+    /// Sets the base URL of the Minecraft services API, which is used with Minecraft access tokens,
+    /// `https://api.minecraftservices.com` by default.
+    pub fn services_url(mut self, url: impl Into<String>) -> Self {
+        self.services_url = trim_url(url.into());
+        self
+    }
+
+    /// Sets the maximum amount of time a single request may take.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the User-Agent header sent with every request.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Sets the client-side rate limit, [`RateLimit::MOJANG`] by default.
+    /// Requests that would exceed the limit are delayed until they fit in it.
+    pub fn rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// Disables the client-side rate limit, for example when talking to a mock server or a caching proxy.
+    pub fn no_rate_limit(mut self) -> Self {
+        self.rate_limit = None;
+        self
+    }
+
+    /// Sets how rate limited (429) and failed (5xx) requests are retried.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Builds a client that uses the default transport.
+    pub fn build(self) -> MojangClient {
+        self.build_with(DefaultTransport::default())
+    }
+
+    /// Builds a client that sends its requests through the provided transport.
+    pub fn build_with<T>(self, transport: T) -> MojangClient<T> {
+        let official =
+            self.api_url == MOJANG_API_URL && self.session_server_url == MOJANG_SESSION_SERVER_URL;
+        let rate_limiter = self.rate_limit.map(|limit| {
+            if official && limit == RateLimit::MOJANG {
+                retry::mojang_bucket()
+            } else {
+                Arc::new(TokenBucket::new(limit))
+            }
+        });
+
+        MojangClient {
+            transport,
+            api_url: self.api_url,
+            session_server_url: self.session_server_url,
+            services_url: self.services_url,
+            timeout: self.timeout,
+            user_agent: self.user_agent,
+            rate_limiter,
+            retry_policy: self.retry_policy,
+        }
+    }
+}
+
+impl Default for MojangClientBuilder {
+    fn default() -> Self {
+        Self {
+            api_url: MOJANG_API_URL.to_owned(),
+            session_server_url: MOJANG_SESSION_SERVER_URL.to_owned(),
+            services_url: MINECRAFT_SERVICES_URL.to_owned(),
+            timeout: None,
+            user_agent: None,
+            rate_limit: Some(RateLimit::MOJANG),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+}
+
+/// The usernames of a bulk lookup, as requested, and deduplicated without the ones Mojang would reject.
+struct BulkLookup {
+    requested: Vec<String>,
+    unique: Vec<String>,
+}
+
+impl BulkLookup {
+    fn new<I, S>(usernames: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut seen = HashSet::new();
+        let mut requested = Vec::new();
+        let mut unique = Vec::new();
+        for username in usernames {
+            let username = username.into();
+            let valid = Username::new_lenient(username.as_str()).is_ok();
+            if valid && seen.insert(username.to_ascii_lowercase()) {
+                unique.push(username.clone());
+            }
+            requested.push(username);
+        }
+
+        Self { requested, unique }
+    }
+
+    fn finish(self, found: Vec<ProfileSummary>) -> HashMap<String, Option<ProfileSummary>> {
+        let found: HashMap<_, _> = found
+            .into_iter()
+            .map(|profile| (profile.name.to_ascii_lowercase(), profile))
+            .collect();
+
+        self.requested
+            .into_iter()
+            .map(|username| {
+                let profile = found.get(&username.to_ascii_lowercase()).cloned();
+                (username, profile)
+            })
+            .collect()
+    }
+}
+
+/// Turns unsuccessful responses into the matching [`Error`].
+pub(crate) fn check_status(response: HttpResponse) -> Result<HttpResponse> {
+    let body = || String::from_utf8_lossy(&response.body).into_owned();
+    match response.status {
+        200..=299 => Ok(response),
+        400 => Err(Error::BadRequest { body: body() }),
+        401 => Err(Error::InvalidToken),
+        404 => Err(Error::NotFound),
+        429 => Err(Error::RateLimited {
+            retry_after: retry::retry_after(&response),
+        }),
+        status @ 500..=599 => Err(Error::ServerError {
+            status,
+            body: body(),
+        }),
+        status => Err(Error::UnexpectedStatus {
+            status,
+            body: body(),
+        }),
+    }
+}
+
+/// Checks the response to a join request, where Yggdrasil reports invalid tokens with a `403 Forbidden`.
+fn check_join_status(response: HttpResponse) -> Result<()> {
+    if response.status == 403 {
+        let error = serde_json::from_slice::<YggdrasilError>(&response.body);
+        if matches!(error, Ok(error) if error.error == "ForbiddenOperationException") {
+            return Err(Error::InvalidToken);
+        }
+    }
+
+    check_status(response).map(drop)
+}
+
+/// Parses the JSON body of a successful response. A `204 No Content` response means that nothing was found.
+pub(crate) fn parse_json<D: DeserializeOwned>(response: HttpResponse) -> Result<D> {
+    parse_optional_json(response)?.ok_or(Error::NotFound)
+}
+
+/// Same as [`parse_json`], but returns [`None`] for a `204 No Content` response.
+fn parse_optional_json<D: DeserializeOwned>(response: HttpResponse) -> Result<Option<D>> {
+    let response = check_status(response)?;
+    if response.status == 204 {
+        return Ok(None);
+    }
+
+    serde_json::from_slice(&response.body)
+        .map(Some)
+        .map_err(|x| Error::MalformedResponse(Box::new(x)))
+}
+
+/// Percent-encodes a query string component.
+pub(crate) fn encode_query(component: &str) -> String {
+    let mut encoded = String::with_capacity(component.len());
+    for byte in component.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => write!(encoded, "%{byte:02X}").unwrap(),
+        }
+    }
+
+    encoded
+}
+
+#[cfg(feature = "async")]
+pub(crate) async fn sleep_async(duration: Duration) {
+    if !duration.is_zero() {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+pub(crate) fn trim_url(mut url: String) -> String {
+    while url.ends_with('/') {
+        url.pop();
+    }
+
+    url
+}
+
+#[cfg(all(test, feature = "online"))]
+mod tests {
+    use super::*;
+    use crate::tests::MockTransport;
+
+    #[test]
+    fn custom_client_settings() {
+        let transport = MockTransport::new()
+            .respond(
+                200,
+                r#"{"id":"069a79f444e94726a5befca90e38aaf5","name":"Notch"}"#,
+            )
+            .respond(
+                200,
+                r#"{"id":"069a79f444e94726a5befca90e38aaf5","name":"Notch"}"#,
+            );
+        let client = MojangClient::builder()
+            .authlib_injector("http://localhost:8080/yggdrasil/")
+            .timeout(Duration::from_secs(3))
+            .user_agent("uuid-mc-tests")
+            .build_with(&transport);
+
+        let uuid = client.get_uuid("Notch").unwrap().unwrap_online();
+        assert_eq!(client.get_username(&uuid).unwrap(), "Notch");
+
+        let requests = transport.requests();
+        assert_eq!(
+            requests[0].url,
+            "http://localhost:8080/yggdrasil/api/users/profiles/minecraft/Notch"
+        );
+        assert_eq!(
+            requests[1].url,
+            "http://localhost:8080/yggdrasil/sessionserver/session/minecraft/profile/069a79f444e94726a5befca90e38aaf5"
+        );
+        for request in requests {
+            assert_eq!(request.timeout, Some(Duration::from_secs(3)));
+            assert_eq!(
+                request.headers,
+                vec![("User-Agent".to_owned(), "uuid-mc-tests".to_owned())]
+            );
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn default_transport() {
+        fn both<T: HttpTransport + AsyncHttpTransport>(_: &MojangClient<T>) {}
+        both(&MojangClient::new());
+    }
+
+    #[test]
+    fn bulk_lookup() {
+        let mut usernames: Vec<_> = (0..11).map(|i| format!("player{i}")).collect();
+        usernames.push("NOTCH".to_owned());
+        usernames.push("notch".to_owned());
+
+        let transport = MockTransport::new().respond(200, "[]").respond(
+            200,
+            r#"[{"id":"069a79f444e94726a5befca90e38aaf5","name":"Notch"}]"#,
+        );
+        let client = MojangClient::with_transport(&transport);
+        let profiles = client.get_uuids(usernames).unwrap();
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].url, "https://api.mojang.com/profiles/minecraft");
+        let second: Vec<String> =
+            serde_json::from_slice(requests[1].body.as_ref().unwrap()).unwrap();
+        assert_eq!(second, ["player10", "NOTCH"]);
+
+        assert_eq!(profiles.len(), 13);
+        assert_eq!(profiles["player3"], None);
+        let notch = profiles["notch"].as_ref().unwrap();
+        assert_eq!(notch.name, "Notch");
+        assert_eq!(profiles["NOTCH"].as_ref(), Some(notch));
+    }
+
+    #[test]
+    fn bulk_lookup_invalid_names() {
+        let transport = MockTransport::new().respond(
+            200,
+            r#"[{"id":"069a79f444e94726a5befca90e38aaf5","name":"Notch"}]"#,
+        );
+        let client = MojangClient::with_transport(&transport);
+        let profiles = client
+            .get_uuids(["Notch", "", "no spaces", "0123456789abcdefg"])
+            .unwrap();
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 1);
+        let sent: Vec<String> = serde_json::from_slice(requests[0].body.as_ref().unwrap()).unwrap();
+        assert_eq!(sent, ["Notch"]);
+
+        assert_eq!(profiles.len(), 4);
+        assert!(profiles["Notch"].is_some());
+        assert_eq!(profiles[""], None);
+        assert_eq!(profiles["no spaces"], None);
+        assert_eq!(profiles["0123456789abcdefg"], None);
+
+        let profiles = client.get_uuids([" "]).unwrap();
+        assert_eq!(profiles[" "], None);
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[test]
+    fn shared_rate_limit() {
+        let bucket = |client: &MojangClient<_>| client.rate_limiter.clone().unwrap();
+
+        let first = MojangClient::with_transport(MockTransport::new());
+        let second = MojangClient::with_transport(MockTransport::new());
+        assert!(Arc::ptr_eq(&bucket(&first), &bucket(&second)));
+
+        let custom = MojangClient::builder()
+            .rate_limit(RateLimit::new(10, Duration::from_secs(1)))
+            .build_with(MockTransport::new());
+        let proxied = MojangClient::builder()
+            .api_url("http://localhost:8080")
+            .build_with(MockTransport::new());
+        assert!(!Arc::ptr_eq(&bucket(&first), &bucket(&custom)));
+        assert!(!Arc::ptr_eq(&bucket(&first), &bucket(&proxied)));
+    }
+
+    #[test]
+    fn zero_rate_limit() {
+        let transport = MockTransport::new().respond(
+            200,
+            r#"{"id":"069a79f444e94726a5befca90e38aaf5","name":"Notch"}"#,
+        );
+        let client = MojangClient::builder()
+            .rate_limit(RateLimit::new(0, Duration::from_secs(1)))
+            .build_with(&transport);
+
+        assert!(client.get_uuid("Notch").is_ok());
+    }
+
+    #[test]
+    fn retries() {
+        let transport = MockTransport::new()
+            .respond(503, "")
+            .respond(429, "")
+            .respond(
+                200,
+                r#"{"id":"069a79f444e94726a5befca90e38aaf5","name":"Notch"}"#,
+            )
+            .respond(429, "")
+            .respond(429, "")
+            .respond(429, "");
+        let client = MojangClient::builder()
+            .retry_policy(RetryPolicy {
+                max_retries: 2,
+                initial_backoff: Duration::ZERO,
+                max_backoff: Duration::ZERO,
+            })
+            .build_with(&transport);
+
+        assert!(client.get_uuid("Notch").is_ok());
+        assert!(matches!(
+            client.get_uuid("Notch"),
+            Err(Error::RateLimited { retry_after: None })
+        ));
+        assert_eq!(transport.requests().len(), 6);
+    }
+
+    #[test]
+    fn profile() {
+        let transport = MockTransport::new().respond(
+            200,
+            r#"{
+                "id": "069a79f444e94726a5befca90e38aaf5",
+                "name": "Notch",
+                "properties": [{"name": "textures", "value": "e30=", "signature": "c2ln"}],
+                "profileActions": []
+            }"#,
+        );
+        let client = MojangClient::with_transport(&transport);
+        let uuid: OnlineUuid =
+            serde_json::from_str(r#""069a79f4-44e9-4726-a5be-fca90e38aaf5""#).unwrap();
+
+        let profile = client.get_profile(&uuid, true).unwrap();
+        assert_eq!(profile.id, uuid);
+        assert_eq!(profile.name, "Notch");
+        let textures = profile.property("textures").unwrap();
+        assert_eq!(textures.value, "e30=");
+        assert_eq!(textures.signature.as_deref(), Some("c2ln"));
+        assert_eq!(
+            transport.requests()[0].url,
+            "https://sessionserver.mojang.com/session/minecraft/profile/069a79f444e94726a5befca90e38aaf5?unsigned=false"
+        );
+    }
+
+    #[test]
+    fn has_joined() {
+        let transport = MockTransport::new()
+            .respond(
+                200,
+                r#"{"id":"069a79f444e94726a5befca90e38aaf5","name":"Notch","properties":[]}"#,
+            )
+            .respond(204, "");
+        let client = MojangClient::with_transport(&transport);
+
+        let profile = client
+            .has_joined("Notch", "-4fc4e4b5", Some("::1".parse().unwrap()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(profile.name, "Notch");
+        assert_eq!(client.has_joined("Notch", "-4fc4e4b5", None).unwrap(), None);
+
+        let requests = transport.requests();
+        assert_eq!(
+            requests[0].url,
+            "https://sessionserver.mojang.com/session/minecraft/hasJoined?username=Notch&serverId=-4fc4e4b5&ip=%3A%3A1"
+        );
+        assert_eq!(
+            requests[1].url,
+            "https://sessionserver.mojang.com/session/minecraft/hasJoined?username=Notch&serverId=-4fc4e4b5"
+        );
+    }
+
+    #[test]
+    fn join() {
+        let transport = MockTransport::new().respond(204, "").respond(
+            403,
+            r#"{"error":"ForbiddenOperationException","errorMessage":"Invalid token."}"#,
+        );
+        let client = MojangClient::with_transport(&transport);
+        let profile: OnlineUuid =
+            serde_json::from_str(r#""069a79f4-44e9-4726-a5be-fca90e38aaf5""#).unwrap();
+
+        client.join("token", &profile, "-4fc4e4b5").unwrap();
+        assert!(matches!(
+            client.join("expired", &profile, "-4fc4e4b5"),
+            Err(Error::InvalidToken)
+        ));
+
+        let request = &transport.requests()[0];
+        assert_eq!(
+            request.url,
+            "https://sessionserver.mojang.com/session/minecraft/join"
+        );
+        let body: serde_json::Value =
+            serde_json::from_slice(request.body.as_ref().unwrap()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "accessToken": "token",
+                "selectedProfile": "069a79f444e94726a5befca90e38aaf5",
+                "serverId": "-4fc4e4b5",
+            })
+        );
+    }
+
+    #[test]
+    fn name_availability() {
+        let transport = MockTransport::new()
+            .respond(200, r#"{"status":"AVAILABLE"}"#)
+            .respond(200, r#"{"status":"NOT_ALLOWED"}"#);
+        let client = MojangClient::with_transport(&transport);
+
+        assert!(client
+            .name_availability("token", "boolean_coercion")
+            .unwrap()
+            .is_available());
+        assert_eq!(
+            client.name_availability("token", "a b").unwrap(),
+            NameAvailability::NotAllowed
+        );
+
+        let requests = transport.requests();
+        assert_eq!(
+            requests[1].url,
+            "https://api.minecraftservices.com/minecraft/profile/name/a%20b/available"
+        );
+        assert!(requests[1]
+            .headers
+            .contains(&("Authorization".to_owned(), "Bearer token".to_owned())));
+    }
+
+    #[test]
+    fn username_escaping() {
+        let transport = MockTransport::new().respond(404, "");
+        let client = MojangClient::with_transport(&transport);
+        let username = Username::new_lenient("Ex/Player").unwrap();
+
+        assert!(matches!(client.get_uuid(&username), Err(Error::NotFound)));
+        assert_eq!(
+            transport.requests()[0].url,
+            "https://api.mojang.com/users/profiles/minecraft/Ex%2FPlayer"
+        );
+    }
+}
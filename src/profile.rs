@@ -0,0 +1,64 @@
+//! Full player profiles, as returned by the Mojang session server.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::OnlineUuid;
+
+/// A player's profile: their UUID, username and profile properties (such as their skin and cape).
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameProfile {
+    pub id: OnlineUuid,
+    pub name: String,
+    #[serde(default)]
+    pub properties: Vec<ProfileProperty>,
+    /// Actions that Mojang requires from the player, such as `FORCED_NAME_CHANGE` or `USING_BANNED_SKIN`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profile_actions: Vec<String>,
+}
+
+impl GameProfile {
+    /// Returns the first property with the given name, if there is one.
+    pub fn property(&self, name: &str) -> Option<&ProfileProperty> {
+        self.properties
+            .iter()
+            .find(|property| property.name == name)
+    }
+}
+
+/// A single profile property. The only property Mojang currently sends is `textures`.
+///
+/// `value` is base64-encoded, and `signature` is only present when the profile was requested signed.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ProfileProperty {
+    pub name: String,
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+/// The player model a skin is meant for.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum SkinModel {
+    /// The classic model with 4 pixel wide arms, used by Steve.
+    #[default]
+    #[serde(alias = "classic")]
+    Classic,
+    /// The slim model with 3 pixel wide arms, used by Alex.
+    #[serde(alias = "slim")]
+    Slim,
+}
+
+impl SkinModel {
+    /// Returns the name Mojang uses for the model when uploading a skin.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Classic => "classic",
+            Self::Slim => "slim",
+        }
+    }
+}
@@ -0,0 +1,285 @@
+//! Encoding and decoding of UUIDs as they appear in Minecraft protocol packets.
+//!
+//! Packets such as Login Success, Player Info and Spawn Player carry a UUID as a 128-bit big-endian value,
+//! which is just its 16 bytes. Before 1.16, Login Success carried it as a protocol string instead:
+//! a VarInt length followed by the UTF-8 hyphenated UUID. The functions in this module handle both forms,
+//! and decoding validates the UUID version like [`PlayerUuid::new_with_uuid`] does.
+//!
+//! The `read_`/`write_` functions work on `std::io` readers and writers, and need the `std` feature.
+//! The `get_`/`put_` functions work on `bytes` buffers, and need the `bytes` feature.
+
+#[cfg(feature = "std")]
+use std::io::{Read, Write};
+
+#[cfg(feature = "bytes")]
+use bytes::{Buf, BufMut};
+
+use crate::{Error, PlayerUuid, Result, Uuid};
+
+/// The length of a hyphenated UUID, which is the longest string the legacy form allows.
+const LEGACY_LENGTH: usize = 36;
+
+/// Reads a VarInt length prefix, one byte at a time.
+fn read_length(mut next_byte: impl FnMut() -> Result<u8>) -> Result<usize> {
+    let mut value: u32 = 0;
+    for i in 0..5 {
+        let byte = next_byte()?;
+        value |= ((byte & 0x7f) as u32) << (7 * i);
+        if byte & 0x80 == 0 {
+            return usize::try_from(value as i32).map_err(|_| Error::MalformedUuid);
+        }
+    }
+
+    Err(Error::MalformedUuid)
+}
+
+/// Checks a legacy string length, so that nothing longer than a UUID is ever read.
+fn check_length(length: usize) -> Result<usize> {
+    if length <= LEGACY_LENGTH {
+        Ok(length)
+    } else {
+        Err(Error::MalformedUuid)
+    }
+}
+
+/// Parses the text of a legacy string. Both the hyphenated form and the undashed one (used before 1.7.6)
+/// are accepted.
+fn parse_legacy(text: &[u8]) -> Result<PlayerUuid> {
+    let text = core::str::from_utf8(text).map_err(|_| Error::MalformedUuid)?;
+    let uuid = Uuid::try_parse(text).map_err(|_| Error::MalformedUuid)?;
+    PlayerUuid::new_with_uuid(uuid)
+}
+
+/// Formats a UUID as a legacy string: the VarInt length (which fits in a single byte) and the hyphenated UUID.
+fn encode_legacy(uuid: Uuid) -> [u8; LEGACY_LENGTH + 1] {
+    let mut encoded = [0; LEGACY_LENGTH + 1];
+    encoded[0] = LEGACY_LENGTH as u8;
+    uuid.hyphenated().encode_lower(&mut encoded[1..]);
+    encoded
+}
+
+#[cfg(feature = "std")]
+fn io_error(error: std::io::Error) -> Error {
+    Error::Io(error.into())
+}
+
+/// Reads a UUID in its 128-bit big-endian form.
+///
+/// # Errors
+/// In case the UUID is neither offline (v3) or online (v4), an [`Error::InvalidUuid`] is returned.
+/// If the reader fails or ends too early, an [`Error::Io`] is returned.
+///
+/// # Examples
+/// ```rust
+/// use uuid_mc::protocol::{read_uuid, write_legacy_uuid};
+/// use uuid_mc::PlayerUuid;
+///
+/// # fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+/// let packet = [6, 154, 121, 244, 68, 233, 71, 38, 165, 190, 252, 169, 14, 56, 170, 245];
+/// let uuid = read_uuid(&mut &packet[..])?;
+/// assert_eq!(uuid.to_string(), "069a79f4-44e9-4726-a5be-fca90e38aaf5");
+///
+/// let mut legacy = Vec::new();
+/// write_legacy_uuid(&mut legacy, uuid)?;
+/// assert_eq!(legacy[0], 36);
+/// assert_eq!(&legacy[1..], b"069a79f4-44e9-4726-a5be-fca90e38aaf5");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub fn read_uuid(reader: &mut impl Read) -> Result<PlayerUuid> {
+    let mut bytes = [0; 16];
+    reader.read_exact(&mut bytes).map_err(io_error)?;
+    PlayerUuid::new_with_uuid(Uuid::from_bytes(bytes))
+}
+
+/// Writes a UUID in its 128-bit big-endian form.
+///
+/// # Errors
+/// If the writer fails, an [`Error::Io`] is returned.
+#[cfg(feature = "std")]
+pub fn write_uuid(writer: &mut impl Write, uuid: impl Into<Uuid>) -> Result<()> {
+    writer.write_all(uuid.into().as_bytes()).map_err(io_error)
+}
+
+/// Reads a UUID in its legacy form, a length-prefixed string.
+///
+/// # Errors
+/// If the string isn't a UUID, an [`Error::MalformedUuid`] is returned,
+/// and in case the UUID is neither offline (v3) or online (v4), an [`Error::InvalidUuid`] is returned.
+/// If the reader fails or ends too early, an [`Error::Io`] is returned.
+#[cfg(feature = "std")]
+pub fn read_legacy_uuid(reader: &mut impl Read) -> Result<PlayerUuid> {
+    let length = check_length(read_length(|| {
+        let mut byte = [0];
+        reader.read_exact(&mut byte).map_err(io_error)?;
+        Ok(byte[0])
+    })?)?;
+
+    let mut text = [0; LEGACY_LENGTH];
+    reader.read_exact(&mut text[..length]).map_err(io_error)?;
+    parse_legacy(&text[..length])
+}
+
+/// Writes a UUID in its legacy form, a length-prefixed hyphenated string.
+///
+/// # Errors
+/// If the writer fails, an [`Error::Io`] is returned.
+#[cfg(feature = "std")]
+pub fn write_legacy_uuid(writer: &mut impl Write, uuid: impl Into<Uuid>) -> Result<()> {
+    writer
+        .write_all(&encode_legacy(uuid.into()))
+        .map_err(io_error)
+}
+
+/// Takes a UUID in its 128-bit big-endian form from the buffer.
+///
+/// # Errors
+/// If the buffer has less than 16 bytes remaining, an [`Error::MalformedUuid`] is returned and nothing is taken.
+/// In case the UUID is neither offline (v3) or online (v4), an [`Error::InvalidUuid`] is returned.
+#[cfg(feature = "bytes")]
+pub fn get_uuid(buf: &mut impl Buf) -> Result<PlayerUuid> {
+    if buf.remaining() < 16 {
+        return Err(Error::MalformedUuid);
+    }
+
+    PlayerUuid::new_with_uuid(Uuid::from_u128(buf.get_u128()))
+}
+
+/// Puts a UUID in its 128-bit big-endian form into the buffer.
+///
+/// # Panics
+/// If the buffer doesn't have enough capacity, like the [`BufMut`] methods.
+#[cfg(feature = "bytes")]
+pub fn put_uuid(buf: &mut impl BufMut, uuid: impl Into<Uuid>) {
+    buf.put_u128(uuid.into().as_u128());
+}
+
+/// Takes a UUID in its legacy form, a length-prefixed string, from the buffer.
+///
+/// # Errors
+/// If the string isn't a UUID or the buffer ends too early, an [`Error::MalformedUuid`] is returned,
+/// and in case the UUID is neither offline (v3) or online (v4), an [`Error::InvalidUuid`] is returned.
+#[cfg(feature = "bytes")]
+pub fn get_legacy_uuid(buf: &mut impl Buf) -> Result<PlayerUuid> {
+    let length = check_length(read_length(|| {
+        if buf.has_remaining() {
+            Ok(buf.get_u8())
+        } else {
+            Err(Error::MalformedUuid)
+        }
+    })?)?;
+    if buf.remaining() < length {
+        return Err(Error::MalformedUuid);
+    }
+
+    let mut text = [0; LEGACY_LENGTH];
+    buf.copy_to_slice(&mut text[..length]);
+    parse_legacy(&text[..length])
+}
+
+/// Puts a UUID in its legacy form, a length-prefixed hyphenated string, into the buffer.
+///
+/// # Panics
+/// If the buffer doesn't have enough capacity, like the [`BufMut`] methods.
+#[cfg(feature = "bytes")]
+pub fn put_legacy_uuid(buf: &mut impl BufMut, uuid: impl Into<Uuid>) {
+    buf.put_slice(&encode_legacy(uuid.into()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTCH: [u8; 16] = [
+        6, 154, 121, 244, 68, 233, 71, 38, 165, 190, 252, 169, 14, 56, 170, 245,
+    ];
+
+    const NOTCH_LEGACY: &[u8] = b"\x24069a79f4-44e9-4726-a5be-fca90e38aaf5";
+
+    fn notch() -> PlayerUuid {
+        PlayerUuid::new_with_uuid(Uuid::from_bytes(NOTCH)).unwrap()
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io() {
+        let mut encoded = Vec::new();
+        write_uuid(&mut encoded, notch()).unwrap();
+        assert_eq!(encoded, NOTCH);
+        assert_eq!(read_uuid(&mut &encoded[..]).unwrap(), notch());
+        assert!(matches!(read_uuid(&mut &NOTCH[..15]), Err(Error::Io(_))));
+        assert!(matches!(
+            read_uuid(&mut &[0; 16][..]),
+            Err(Error::InvalidUuid)
+        ));
+
+        let mut encoded = Vec::new();
+        write_legacy_uuid(&mut encoded, notch().unwrap_online()).unwrap();
+        assert_eq!(encoded, NOTCH_LEGACY);
+        assert_eq!(read_legacy_uuid(&mut &encoded[..]).unwrap(), notch());
+        assert_eq!(
+            read_legacy_uuid(&mut &b"\x20069a79f444e94726a5befca90e38aaf5"[..]).unwrap(),
+            notch()
+        );
+        assert!(matches!(
+            read_legacy_uuid(&mut &NOTCH_LEGACY[..20]),
+            Err(Error::Io(_))
+        ));
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn bytes() {
+        let mut encoded = Vec::new();
+        put_uuid(&mut encoded, notch());
+        put_legacy_uuid(&mut encoded, notch());
+        assert_eq!(encoded[..16], NOTCH);
+        assert_eq!(encoded[16..], *NOTCH_LEGACY);
+
+        let mut buf = &encoded[..];
+        assert_eq!(get_uuid(&mut buf).unwrap(), notch());
+        assert_eq!(get_legacy_uuid(&mut buf).unwrap(), notch());
+        assert!(!buf.has_remaining());
+
+        let mut short = &NOTCH[..15];
+        assert!(matches!(get_uuid(&mut short), Err(Error::MalformedUuid)));
+        assert_eq!(short.len(), 15);
+        assert!(matches!(
+            get_legacy_uuid(&mut &NOTCH_LEGACY[..20]),
+            Err(Error::MalformedUuid)
+        ));
+    }
+
+    /// Malformed legacy strings, and whether a reader fails on them by ending too early.
+    const MALFORMED_LEGACY: [(&[u8], bool); 6] = [
+        (b"", true),
+        (b"\x80\x80\x80\x80\x80\x01", false),
+        (b"\xff\xff\xff\xff\x0f", false),
+        (b"\x25069a79f4-44e9-4726-a5be-fca90e38aaf50", false),
+        (b"\x24069a79f4-44e9-4726-a5be-fca90e38aafg", false),
+        (b"\x04\xff\xfe\xfd\xfc", false),
+    ];
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn malformed_legacy_io() {
+        for (malformed, ends_early) in MALFORMED_LEGACY {
+            let result = read_legacy_uuid(&mut &malformed[..]);
+            if ends_early {
+                assert!(matches!(result, Err(Error::Io(_))), "{malformed:?}");
+            } else {
+                assert!(matches!(result, Err(Error::MalformedUuid)), "{malformed:?}");
+            }
+        }
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn malformed_legacy_bytes() {
+        for (malformed, _) in MALFORMED_LEGACY {
+            let result = get_legacy_uuid(&mut &malformed[..]);
+            assert!(matches!(result, Err(Error::MalformedUuid)), "{malformed:?}");
+        }
+    }
+}
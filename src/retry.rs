@@ -0,0 +1,230 @@
+//! Client-side rate limiting and retry policies for Mojang API calls.
+
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::transport::HttpResponse;
+
+/// A limit on the number of requests a [`MojangClient`](crate::MojangClient) may send in a period of time.
+///
+/// The limit is enforced with a token bucket: up to `requests` requests may be sent in a burst,
+/// after which requests are delayed until the bucket refills.
+/// A limit of zero requests is treated as one request, and a limit over a zero period doesn't delay anything.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct RateLimit {
+    pub requests: u32,
+    pub per: Duration,
+}
+
+impl RateLimit {
+    /// Mojang's documented limit of 600 requests per 10 minutes.
+    pub const MOJANG: Self = Self::new(600, Duration::from_secs(600));
+
+    /// Creates a limit of `requests` requests per `per`.
+    pub const fn new(requests: u32, per: Duration) -> Self {
+        Self { requests, per }
+    }
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self::MOJANG
+    }
+}
+
+/// How a [`MojangClient`](crate::MojangClient) retries requests that were rate limited (429)
+/// or failed with a server error (5xx).
+///
+/// The server's `Retry-After` header is honoured when present; otherwise, the delay starts at
+/// `initial_backoff` and doubles after every attempt, up to `max_backoff`. A request whose `Retry-After`
+/// is longer than `max_backoff` isn't retried, so that the error (such as [`Error::RateLimited`](crate::Error::RateLimited),
+/// which holds the requested delay) is returned right away.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub const NONE: Self = Self {
+        max_retries: 0,
+        initial_backoff: Duration::ZERO,
+        max_backoff: Duration::ZERO,
+    };
+
+    /// Returns the delay before the next attempt, or [`None`] if the response should not be retried.
+    /// `attempt` is the number of retries that have already been made.
+    pub(crate) fn delay(&self, response: &HttpResponse, attempt: u32) -> Option<Duration> {
+        let retryable = response.status == 429 || (500..600).contains(&response.status);
+        if !retryable || attempt >= self.max_retries {
+            return None;
+        }
+
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff);
+
+        match retry_after(response) {
+            Some(delay) if delay > self.max_backoff => None,
+            Some(delay) => Some(delay),
+            None => Some(backoff),
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Parses the `Retry-After` header of a response, if it is given in seconds.
+pub(crate) fn retry_after(response: &HttpResponse) -> Option<Duration> {
+    let seconds = response.header("Retry-After")?.trim().parse().ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+/// Returns the token bucket shared by every client that uses [`RateLimit::MOJANG`] with the official API,
+/// since Mojang applies its limit per IP address rather than per client.
+pub(crate) fn mojang_bucket() -> Arc<TokenBucket> {
+    static BUCKET: OnceLock<Arc<TokenBucket>> = OnceLock::new();
+    BUCKET
+        .get_or_init(|| Arc::new(TokenBucket::new(RateLimit::MOJANG)))
+        .clone()
+}
+
+/// The token bucket that enforces a [`RateLimit`], shared between clones of a client.
+#[derive(Debug)]
+pub(crate) struct TokenBucket {
+    limit: RateLimit,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            state: Mutex::new(BucketState {
+                tokens: f64::from(limit.requests.max(1)),
+                updated: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes a token from the bucket, returning how long the caller has to wait before using it.
+    ///
+    /// Tokens are reserved even when the bucket is empty, so concurrent callers queue up behind each other
+    /// instead of all waking up at the same time.
+    pub(crate) fn acquire(&self) -> Duration {
+        if self.limit.per.is_zero() {
+            return Duration::ZERO;
+        }
+        let capacity = f64::from(self.limit.requests.max(1));
+        let rate = capacity / self.limit.per.as_secs_f64();
+
+        let mut state = self.state.lock().unwrap_or_else(|x| x.into_inner());
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.updated).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * rate).min(capacity) - 1.0;
+        state.updated = now;
+
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::try_from_secs_f64(-state.tokens / rate).unwrap_or(self.limit.per)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: u16, retry_after: Option<&str>) -> HttpResponse {
+        HttpResponse {
+            status,
+            headers: retry_after
+                .map(|value| vec![("retry-after".to_owned(), value.to_owned())])
+                .unwrap_or_default(),
+            body: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn retry_delays() {
+        let policy = RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(3),
+        };
+
+        assert_eq!(policy.delay(&response(200, None), 0), None);
+        assert_eq!(policy.delay(&response(404, None), 0), None);
+        assert_eq!(
+            policy.delay(&response(503, None), 0),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(
+            policy.delay(&response(429, None), 1),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            policy.delay(&response(429, None), 2),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(
+            policy.delay(&response(429, Some("2")), 0),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(policy.delay(&response(429, Some("3600")), 0), None);
+        assert_eq!(policy.delay(&response(429, None), 3), None);
+    }
+
+    #[test]
+    fn token_bucket() {
+        let bucket = TokenBucket::new(RateLimit::new(2, Duration::from_secs(10)));
+
+        assert_eq!(bucket.acquire(), Duration::ZERO);
+        assert_eq!(bucket.acquire(), Duration::ZERO);
+
+        let wait = bucket.acquire();
+        assert!(wait > Duration::from_secs(4) && wait <= Duration::from_secs(5));
+        let wait = bucket.acquire();
+        assert!(wait > Duration::from_secs(9) && wait <= Duration::from_secs(10));
+    }
+
+    #[test]
+    fn degenerate_limits() {
+        let bucket = TokenBucket::new(RateLimit::new(0, Duration::from_secs(10)));
+        assert_eq!(bucket.acquire(), Duration::ZERO);
+        let wait = bucket.acquire();
+        assert!(wait > Duration::from_secs(9) && wait <= Duration::from_secs(10));
+
+        for limit in [
+            RateLimit::new(0, Duration::ZERO),
+            RateLimit::new(5, Duration::ZERO),
+        ] {
+            let bucket = TokenBucket::new(limit);
+            for _ in 0..10 {
+                assert_eq!(bucket.acquire(), Duration::ZERO);
+            }
+        }
+
+        let bucket = TokenBucket::new(RateLimit::new(1, Duration::MAX));
+        assert_eq!(bucket.acquire(), Duration::ZERO);
+        assert!(bucket.acquire() > Duration::ZERO);
+    }
+}
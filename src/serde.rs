@@ -0,0 +1,360 @@
+//! Modules for use with `#[serde(with = "...")]`, one for every format Minecraft UUIDs appear in.
+//!
+//! By default, [`PlayerUuid`], [`OnlineUuid`] and [`OfflineUuid`] serialize as hyphenated strings.
+//! Each module here serializes them in another format instead, and works on the UUID types themselves
+//! as well as on their `Option` and `Vec` forms. Deserialization validates the UUID version like
+//! [`PlayerUuid::new_with_uuid`] does.
+//!
+//! - [`simple`]: an undashed hexadecimal string, as used by the Mojang API.
+//! - [`hyphenated`]: a hyphenated hexadecimal string.
+//! - [`int_array`]: an array of four `i32`s, as used by NBT. See the [`crate::nbt`] module.
+//! - [`most_least`]: a pair of `i64`s, the most and least significant bits, as used by legacy configs.
+//! - [`bytes`]: a base64 string of the 16 bytes in human-readable formats, and the raw bytes in binary ones.
+//!
+//! # Examples
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use uuid_mc::{OfflineUuid, OnlineUuid};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Whitelist {
+//!     #[serde(with = "uuid_mc::serde::simple")]
+//!     owner: OnlineUuid,
+//!     #[serde(with = "uuid_mc::serde::int_array")]
+//!     guests: Vec<OfflineUuid>,
+//!     #[serde(with = "uuid_mc::serde::most_least", default)]
+//!     banned: Option<OnlineUuid>,
+//! }
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+use ::serde::de::{self, Deserializer, Visitor};
+use ::serde::ser::Serializer;
+use ::serde::{Deserialize, Serialize};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use crate::{nbt, OfflineUuid, OnlineUuid, PlayerUuid, Uuid};
+
+mod private {
+    use super::*;
+
+    /// A player UUID type: [`PlayerUuid`], [`OnlineUuid`] or [`OfflineUuid`].
+    pub trait PlayerUuidType: Copy {
+        fn to_uuid(self) -> Uuid;
+        fn from_uuid(uuid: Uuid) -> crate::Result<Self>;
+    }
+
+    /// A format that UUIDs can be (de)serialized in.
+    pub trait Format {
+        fn serialize<S: Serializer>(uuid: Uuid, serializer: S) -> Result<S::Ok, S::Error>;
+        fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Uuid, D::Error>;
+    }
+}
+
+use private::{Format, PlayerUuidType};
+
+/// A value that the modules in [`crate::serde`] can (de)serialize: [`PlayerUuid`], [`OnlineUuid`],
+/// [`OfflineUuid`], or an `Option` or `Vec` of them.
+///
+/// This trait is sealed, and can't be implemented outside of this crate.
+pub trait UuidField: Sized {
+    #[doc(hidden)]
+    fn serialize_as<F: Format, S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+    #[doc(hidden)]
+    fn deserialize_as<'de, F: Format, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error>;
+}
+
+macro_rules! player_uuid_type {
+    ($($ty:ty => $from_uuid:path),* $(,)?) => {$(
+        impl PlayerUuidType for $ty {
+            fn to_uuid(self) -> Uuid {
+                *self.as_uuid()
+            }
+
+            fn from_uuid(uuid: Uuid) -> crate::Result<Self> {
+                $from_uuid(uuid)
+            }
+        }
+    )*};
+}
+
+player_uuid_type! {
+    PlayerUuid => PlayerUuid::new_with_uuid,
+    OnlineUuid => OnlineUuid::try_from,
+    OfflineUuid => OfflineUuid::try_from,
+}
+
+/// A player UUID that (de)serializes in the format `F`.
+struct With<F, T>(T, PhantomData<F>);
+
+impl<F: Format, T: PlayerUuidType> Serialize for With<F, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        F::serialize(self.0.to_uuid(), serializer)
+    }
+}
+
+impl<'de, F: Format, T: PlayerUuidType> Deserialize<'de> for With<F, T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let uuid = F::deserialize(deserializer)?;
+        T::from_uuid(uuid)
+            .map(|x| With(x, PhantomData))
+            .map_err(de::Error::custom)
+    }
+}
+
+impl<T: PlayerUuidType> UuidField for T {
+    fn serialize_as<F: Format, S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        F::serialize(self.to_uuid(), serializer)
+    }
+
+    fn deserialize_as<'de, F: Format, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        With::<F, T>::deserialize(deserializer).map(|x| x.0)
+    }
+}
+
+impl<T: PlayerUuidType> UuidField for Option<T> {
+    fn serialize_as<F: Format, S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.map(|x| With::<F, T>(x, PhantomData))
+            .serialize(serializer)
+    }
+
+    fn deserialize_as<'de, F: Format, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        Option::<With<F, T>>::deserialize(deserializer).map(|x| x.map(|x| x.0))
+    }
+}
+
+impl<T: PlayerUuidType> UuidField for Vec<T> {
+    fn serialize_as<F: Format, S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(|x| With::<F, T>(*x, PhantomData)))
+    }
+
+    fn deserialize_as<'de, F: Format, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let uuids = Vec::<With<F, T>>::deserialize(deserializer)?;
+        Ok(uuids.into_iter().map(|x| x.0).collect())
+    }
+}
+
+macro_rules! format_module {
+    ($(#[$doc:meta])* $module:ident, $format:ident) => {
+        $(#[$doc])*
+        pub mod $module {
+            use super::*;
+
+            /// Serializes the value in this module's format.
+            pub fn serialize<T: UuidField, S: Serializer>(
+                value: &T,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                value.serialize_as::<$format, S>(serializer)
+            }
+
+            /// Deserializes a value in this module's format.
+            pub fn deserialize<'de, T: UuidField, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<T, D::Error> {
+                T::deserialize_as::<$format, D>(deserializer)
+            }
+        }
+    };
+}
+
+format_module! {
+    /// (De)serializes UUIDs as undashed hexadecimal strings, such as `069a79f444e94726a5befca90e38aaf5`.
+    /// Any textual UUID format is accepted when deserializing.
+    simple, Simple
+}
+
+format_module! {
+    /// (De)serializes UUIDs as hyphenated hexadecimal strings, such as `069a79f4-44e9-4726-a5be-fca90e38aaf5`.
+    /// Any textual UUID format is accepted when deserializing.
+    hyphenated, Hyphenated
+}
+
+format_module! {
+    /// (De)serializes UUIDs as arrays of four `i32`s, such as `[110787060, 1156138790, -1514210135, 238594805]`.
+    int_array, IntArray
+}
+
+format_module! {
+    /// (De)serializes UUIDs as pairs of `i64`s, the most and least significant bits,
+    /// such as `[475826800676128550, -6503483008858150155]`.
+    most_least, MostLeast
+}
+
+format_module! {
+    /// (De)serializes UUIDs as base64 strings of their 16 bytes (such as `Bpp59ETpRyalvvypDjiq9Q==`)
+    /// in human-readable formats, and as raw bytes in binary ones.
+    bytes, Bytes
+}
+
+struct Simple;
+struct Hyphenated;
+struct IntArray;
+struct MostLeast;
+struct Bytes;
+
+fn parse_str<E: de::Error>(value: &str) -> Result<Uuid, E> {
+    Uuid::try_parse(value).map_err(E::custom)
+}
+
+impl Format for Simple {
+    fn serialize<S: Serializer>(uuid: Uuid, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&uuid.simple())
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Uuid, D::Error> {
+        parse_str(&String::deserialize(deserializer)?)
+    }
+}
+
+impl Format for Hyphenated {
+    fn serialize<S: Serializer>(uuid: Uuid, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&uuid.hyphenated())
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Uuid, D::Error> {
+        parse_str(&String::deserialize(deserializer)?)
+    }
+}
+
+impl Format for IntArray {
+    fn serialize<S: Serializer>(uuid: Uuid, serializer: S) -> Result<S::Ok, S::Error> {
+        nbt::to_int_array(&uuid).serialize(serializer)
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Uuid, D::Error> {
+        <[i32; 4]>::deserialize(deserializer).map(nbt::from_int_array)
+    }
+}
+
+impl Format for MostLeast {
+    fn serialize<S: Serializer>(uuid: Uuid, serializer: S) -> Result<S::Ok, S::Error> {
+        nbt::to_most_least(&uuid).serialize(serializer)
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Uuid, D::Error> {
+        let (most, least) = <(i64, i64)>::deserialize(deserializer)?;
+        Ok(nbt::from_most_least(most, least))
+    }
+}
+
+impl Format for Bytes {
+    fn serialize<S: Serializer>(uuid: Uuid, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&STANDARD.encode(uuid.as_bytes()))
+        } else {
+            serializer.serialize_bytes(uuid.as_bytes())
+        }
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Uuid, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(BytesVisitor)
+        } else {
+            deserializer.deserialize_bytes(BytesVisitor)
+        }
+    }
+}
+
+struct BytesVisitor;
+
+impl Visitor<'_> for BytesVisitor {
+    type Value = Uuid;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("16 bytes, or a base64 string of them")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Uuid, E> {
+        let bytes = STANDARD.decode(value).map_err(E::custom)?;
+        self.visit_bytes(&bytes)
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Uuid, E> {
+        Uuid::from_slice(value).map_err(E::custom)
+    }
+}
+
+#[cfg(all(test, feature = "serde_json"))]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Formats {
+        #[serde(with = "simple")]
+        simple: OnlineUuid,
+        #[serde(with = "hyphenated")]
+        hyphenated: PlayerUuid,
+        #[serde(with = "int_array")]
+        int_array: Vec<OnlineUuid>,
+        #[serde(with = "most_least")]
+        most_least: Option<OfflineUuid>,
+        #[serde(with = "bytes")]
+        bytes: Option<PlayerUuid>,
+    }
+
+    #[test]
+    fn formats() {
+        let online = PlayerUuid::new_with_uuid(
+            Uuid::try_parse("069a79f4-44e9-4726-a5be-fca90e38aaf5").unwrap(),
+        )
+        .unwrap();
+        let offline = PlayerUuid::new_with_uuid(
+            Uuid::try_parse("db62bdfb-eddc-3acc-a14e-c703aba52549").unwrap(),
+        )
+        .unwrap();
+
+        let formats = Formats {
+            simple: online.unwrap_online(),
+            hyphenated: offline,
+            int_array: vec![online.unwrap_online()],
+            most_least: Some(offline.unwrap_offline()),
+            bytes: Some(online),
+        };
+        let json = json!({
+            "simple": "069a79f444e94726a5befca90e38aaf5",
+            "hyphenated": "db62bdfb-eddc-3acc-a14e-c703aba52549",
+            "int_array": [[110787060, 1156138790, -1514210135, 238594805]],
+            "most_least": [-2638337541960615220i64, -6823297566841166519i64],
+            "bytes": "Bpp59ETpRyalvvypDjiq9Q==",
+        });
+
+        assert_eq!(serde_json::to_value(&formats).unwrap(), json);
+        assert_eq!(serde_json::from_value::<Formats>(json).unwrap(), formats);
+    }
+
+    #[test]
+    fn validation() {
+        let mut json = json!({
+            "simple": "069a79f4-44e9-4726-a5be-fca90e38aaf5",
+            "hyphenated": "db62bdfbeddc3acca14ec703aba52549",
+            "int_array": [],
+            "most_least": null,
+            "bytes": null,
+        });
+        assert!(serde_json::from_value::<Formats>(json.clone()).is_ok());
+
+        json["simple"] = json!("db62bdfbeddc3acca14ec703aba52549");
+        assert!(serde_json::from_value::<Formats>(json.clone()).is_err());
+
+        json["simple"] = json!("069a79f444e94726a5befca90e38aaf5");
+        json["bytes"] = json!("not base64!");
+        assert!(serde_json::from_value::<Formats>(json).is_err());
+    }
+}
@@ -0,0 +1,497 @@
+//! The authenticated Minecraft Services API, which manages the profile of the account an access token belongs to.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::online::{encode_query, parse_json, NameAvailability};
+#[cfg(feature = "async")]
+use crate::transport::AsyncHttpTransport;
+#[cfg(feature = "online")]
+use crate::transport::HttpTransport;
+use crate::transport::{DefaultTransport, HttpRequest, Method};
+use crate::{MojangClient, OnlineUuid, Result, SkinModel};
+
+/// The boundary that separates the parts of a skin upload, unless the skin happens to contain it.
+const MULTIPART_BOUNDARY: &str = "uuid-mc-skin-upload";
+
+/// The profile of the account an access token belongs to, including all of its skins and capes.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MinecraftProfile {
+    pub id: OnlineUuid,
+    pub name: String,
+    #[serde(default)]
+    pub skins: Vec<ProfileSkin>,
+    #[serde(default)]
+    pub capes: Vec<ProfileCape>,
+}
+
+/// A skin that an account has uploaded.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ProfileSkin {
+    pub id: String,
+    pub state: TextureState,
+    pub url: String,
+    #[serde(default)]
+    pub variant: SkinModel,
+    /// The name of the skin, for Mojang's default skins.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+}
+
+/// A cape that an account owns.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ProfileCape {
+    pub id: String,
+    pub state: TextureState,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+}
+
+/// Whether a skin or cape is the one currently shown.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TextureState {
+    Active,
+    Inactive,
+}
+
+impl MinecraftProfile {
+    /// Returns the skin the player currently uses, if they have one set.
+    pub fn active_skin(&self) -> Option<&ProfileSkin> {
+        self.skins
+            .iter()
+            .find(|skin| skin.state == TextureState::Active)
+    }
+
+    /// Returns the cape the player currently shows, if any.
+    pub fn active_cape(&self) -> Option<&ProfileCape> {
+        self.capes
+            .iter()
+            .find(|cape| cape.state == TextureState::Active)
+    }
+}
+
+/// A client for the Minecraft Services API, authenticated with a Minecraft access token.
+///
+/// An access token can be obtained through the `auth` module, when the `auth` feature is enabled.
+///
+/// # Examples
+/// ```rust,no_run
+/// use uuid_mc::services::MinecraftServices;
+/// use uuid_mc::SkinModel;
+///
+/// # fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+/// let services = MinecraftServices::new("<access token>");
+///
+/// let profile = services.profile()?;
+/// println!("logged in as {}", profile.name);
+///
+/// services.set_skin(SkinModel::Slim, "https://example.com/skin.png")?;
+/// services.hide_cape()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MinecraftServices<T = DefaultTransport> {
+    client: MojangClient<T>,
+    access_token: String,
+}
+
+impl MinecraftServices {
+    /// Creates a client for the given access token, using a default [`MojangClient`].
+    pub fn new(access_token: impl Into<String>) -> Self {
+        Self::with_client(MojangClient::new(), access_token)
+    }
+}
+
+impl<T> MinecraftServices<T> {
+    /// Creates a client for the given access token, which sends its requests through the provided client.
+    ///
+    /// The client's [services URL](crate::MojangClientBuilder::services_url) and request settings are used.
+    pub fn with_client(client: MojangClient<T>, access_token: impl Into<String>) -> Self {
+        Self {
+            client,
+            access_token: access_token.into(),
+        }
+    }
+
+    fn request(&self, method: Method, path: &str) -> HttpRequest {
+        let url = format!("{}/minecraft/profile{}", self.client.services_url(), path);
+        self.client
+            .configure(HttpRequest::new(method, url))
+            .header("Authorization", format!("Bearer {}", self.access_token))
+    }
+
+    fn name_request(&self, method: Method, name: &str, suffix: &str) -> HttpRequest {
+        self.request(method, &format!("/name/{}{}", encode_query(name), suffix))
+    }
+
+    fn skin_url_request(&self, model: SkinModel, url: &str) -> HttpRequest {
+        let body = json!({ "variant": model.as_str(), "url": url });
+        self.json_request(Method::Post, "/skins", &body)
+    }
+
+    fn skin_upload_request(&self, model: SkinModel, png: &[u8]) -> HttpRequest {
+        let boundary = multipart_boundary(png);
+        let mut body = format!(
+            "--{boundary}\r\n\
+             Content-Disposition: form-data; name=\"variant\"\r\n\r\n\
+             {}\r\n\
+             --{boundary}\r\n\
+             Content-Disposition: form-data; name=\"file\"; filename=\"skin.png\"\r\n\
+             Content-Type: image/png\r\n\r\n",
+            model.as_str()
+        )
+        .into_bytes();
+        body.extend_from_slice(png);
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+        let mut request = self.request(Method::Post, "/skins").header(
+            "Content-Type",
+            format!("multipart/form-data; boundary={boundary}"),
+        );
+        request.body = Some(body);
+        request
+    }
+
+    fn show_cape_request(&self, cape_id: &str) -> HttpRequest {
+        let body = json!({ "capeId": cape_id });
+        self.json_request(Method::Put, "/capes/active", &body)
+    }
+
+    fn json_request(&self, method: Method, path: &str, body: &serde_json::Value) -> HttpRequest {
+        let mut request = self
+            .request(method, path)
+            .header("Content-Type", "application/json");
+        request.body = Some(body.to_string().into_bytes());
+        request
+    }
+
+    /// Fetches the profile of the account, including its skins and capes.
+    ///
+    /// # Errors
+    /// If the account doesn't own Minecraft, an [`Error::NotFound`](crate::Error::NotFound) is returned.
+    /// See [`Error`](enum@crate::Error) for the other ways the request can fail.
+    #[cfg(feature = "online")]
+    pub fn profile(&self) -> Result<MinecraftProfile>
+    where
+        T: HttpTransport,
+    {
+        parse_json(self.client.send(self.request(Method::Get, ""))?)
+    }
+
+    /// The `async` equivalent of [`profile`](Self::profile).
+    #[cfg(feature = "async")]
+    pub async fn profile_async(&self) -> Result<MinecraftProfile>
+    where
+        T: AsyncHttpTransport,
+    {
+        parse_json(
+            self.client
+                .send_async(self.request(Method::Get, ""))
+                .await?,
+        )
+    }
+
+    /// Checks whether the account could change its name to the given one.
+    /// See [`MojangClient::name_availability`].
+    #[cfg(feature = "online")]
+    pub fn name_availability(&self, name: &str) -> Result<NameAvailability>
+    where
+        T: HttpTransport,
+    {
+        self.client.name_availability(&self.access_token, name)
+    }
+
+    /// The `async` equivalent of [`name_availability`](Self::name_availability).
+    #[cfg(feature = "async")]
+    pub async fn name_availability_async(&self, name: &str) -> Result<NameAvailability>
+    where
+        T: AsyncHttpTransport,
+    {
+        self.client
+            .name_availability_async(&self.access_token, name)
+            .await
+    }
+
+    /// Changes the account's name, returning the updated profile.
+    ///
+    /// # Errors
+    /// If the name is invalid, an [`Error::BadRequest`](crate::Error::BadRequest) is returned.
+    /// If the name is taken, or the account can't change its name yet, an
+    /// [`Error::UnexpectedStatus`](crate::Error::UnexpectedStatus) with status 403 is returned.
+    /// See [`Error`](enum@crate::Error) for the other ways the request can fail.
+    #[cfg(feature = "online")]
+    pub fn change_name(&self, name: &str) -> Result<MinecraftProfile>
+    where
+        T: HttpTransport,
+    {
+        parse_json(self.client.send(self.name_request(Method::Put, name, ""))?)
+    }
+
+    /// The `async` equivalent of [`change_name`](Self::change_name).
+    #[cfg(feature = "async")]
+    pub async fn change_name_async(&self, name: &str) -> Result<MinecraftProfile>
+    where
+        T: AsyncHttpTransport,
+    {
+        let request = self.name_request(Method::Put, name, "");
+        parse_json(self.client.send_async(request).await?)
+    }
+
+    /// Sets the account's skin to the PNG image at the given URL, returning the updated profile.
+    ///
+    /// # Errors
+    /// If the image isn't a valid skin, an [`Error::BadRequest`](crate::Error::BadRequest) is returned.
+    /// See [`Error`](enum@crate::Error) for the other ways the request can fail.
+    #[cfg(feature = "online")]
+    pub fn set_skin(&self, model: SkinModel, url: &str) -> Result<MinecraftProfile>
+    where
+        T: HttpTransport,
+    {
+        parse_json(self.client.send(self.skin_url_request(model, url))?)
+    }
+
+    /// The `async` equivalent of [`set_skin`](Self::set_skin).
+    #[cfg(feature = "async")]
+    pub async fn set_skin_async(&self, model: SkinModel, url: &str) -> Result<MinecraftProfile>
+    where
+        T: AsyncHttpTransport,
+    {
+        let request = self.skin_url_request(model, url);
+        parse_json(self.client.send_async(request).await?)
+    }
+
+    /// Uploads a PNG image as the account's skin, returning the updated profile.
+    ///
+    /// # Errors
+    /// See [`set_skin`](Self::set_skin).
+    #[cfg(feature = "online")]
+    pub fn upload_skin(&self, model: SkinModel, png: &[u8]) -> Result<MinecraftProfile>
+    where
+        T: HttpTransport,
+    {
+        parse_json(self.client.send(self.skin_upload_request(model, png))?)
+    }
+
+    /// The `async` equivalent of [`upload_skin`](Self::upload_skin).
+    #[cfg(feature = "async")]
+    pub async fn upload_skin_async(&self, model: SkinModel, png: &[u8]) -> Result<MinecraftProfile>
+    where
+        T: AsyncHttpTransport,
+    {
+        let request = self.skin_upload_request(model, png);
+        parse_json(self.client.send_async(request).await?)
+    }
+
+    /// Resets the account's skin to the default one, returning the updated profile.
+    ///
+    /// # Errors
+    /// See [`Error`](enum@crate::Error) for the ways the request can fail.
+    #[cfg(feature = "online")]
+    pub fn reset_skin(&self) -> Result<MinecraftProfile>
+    where
+        T: HttpTransport,
+    {
+        parse_json(
+            self.client
+                .send(self.request(Method::Delete, "/skins/active"))?,
+        )
+    }
+
+    /// The `async` equivalent of [`reset_skin`](Self::reset_skin).
+    #[cfg(feature = "async")]
+    pub async fn reset_skin_async(&self) -> Result<MinecraftProfile>
+    where
+        T: AsyncHttpTransport,
+    {
+        let request = self.request(Method::Delete, "/skins/active");
+        parse_json(self.client.send_async(request).await?)
+    }
+
+    /// Shows the cape with the given ID, which the account has to own, returning the updated profile.
+    ///
+    /// # Errors
+    /// If the account doesn't own the cape, an [`Error::BadRequest`](crate::Error::BadRequest) is returned.
+    /// See [`Error`](enum@crate::Error) for the other ways the request can fail.
+    #[cfg(feature = "online")]
+    pub fn show_cape(&self, cape_id: &str) -> Result<MinecraftProfile>
+    where
+        T: HttpTransport,
+    {
+        parse_json(self.client.send(self.show_cape_request(cape_id))?)
+    }
+
+    /// The `async` equivalent of [`show_cape`](Self::show_cape).
+    #[cfg(feature = "async")]
+    pub async fn show_cape_async(&self, cape_id: &str) -> Result<MinecraftProfile>
+    where
+        T: AsyncHttpTransport,
+    {
+        let request = self.show_cape_request(cape_id);
+        parse_json(self.client.send_async(request).await?)
+    }
+
+    /// Hides the account's cape, returning the updated profile.
+    ///
+    /// # Errors
+    /// See [`Error`](enum@crate::Error) for the ways the request can fail.
+    #[cfg(feature = "online")]
+    pub fn hide_cape(&self) -> Result<MinecraftProfile>
+    where
+        T: HttpTransport,
+    {
+        parse_json(
+            self.client
+                .send(self.request(Method::Delete, "/capes/active"))?,
+        )
+    }
+
+    /// The `async` equivalent of [`hide_cape`](Self::hide_cape).
+    #[cfg(feature = "async")]
+    pub async fn hide_cape_async(&self) -> Result<MinecraftProfile>
+    where
+        T: AsyncHttpTransport,
+    {
+        let request = self.request(Method::Delete, "/capes/active");
+        parse_json(self.client.send_async(request).await?)
+    }
+}
+
+/// Picks a multipart boundary that doesn't appear in `content`, by numbering [`MULTIPART_BOUNDARY`] if needed.
+fn multipart_boundary(content: &[u8]) -> String {
+    let contains = |boundary: &str| {
+        content
+            .windows(boundary.len())
+            .any(|window| window == boundary.as_bytes())
+    };
+
+    let mut boundary = MULTIPART_BOUNDARY.to_owned();
+    let mut suffix = 0;
+    while contains(&boundary) {
+        suffix += 1;
+        boundary = format!("{MULTIPART_BOUNDARY}-{suffix}");
+    }
+
+    boundary
+}
+
+#[cfg(all(test, feature = "online"))]
+mod tests {
+    use super::*;
+    use crate::tests::MockTransport;
+    use crate::Error;
+
+    const PROFILE: &str = r#"{
+        "id": "069a79f444e94726a5befca90e38aaf5",
+        "name": "Notch",
+        "skins": [{
+            "id": "6a6e65e5-76dd-4c3c-a625-162924514568",
+            "state": "ACTIVE",
+            "url": "http://textures.minecraft.net/texture/292ef567",
+            "variant": "CLASSIC"
+        }],
+        "capes": [{
+            "id": "1981aad3-73b6-4a49-8e01-9e55b1d5a0f3",
+            "state": "INACTIVE",
+            "url": "http://textures.minecraft.net/texture/953cac8b",
+            "alias": "Migrator"
+        }]
+    }"#;
+
+    #[test]
+    fn profile_management() {
+        let transport = MockTransport::new()
+            .respond(200, PROFILE)
+            .respond(200, r#"{"status":"DUPLICATE"}"#)
+            .respond(200, PROFILE)
+            .respond(200, PROFILE)
+            .respond(200, PROFILE)
+            .respond(401, "");
+        let client = MojangClient::builder()
+            .services_url("http://localhost/")
+            .build_with(&transport);
+        let services = MinecraftServices::with_client(client, "token");
+
+        let profile = services.profile().unwrap();
+        assert_eq!(profile.name, "Notch");
+        assert_eq!(profile.active_skin().unwrap().variant, SkinModel::Classic);
+        assert_eq!(profile.active_cape(), None);
+        assert_eq!(profile.capes[0].alias.as_deref(), Some("Migrator"));
+
+        assert_eq!(
+            services.name_availability("jeb_").unwrap(),
+            NameAvailability::Duplicate
+        );
+        services
+            .set_skin(SkinModel::Slim, "https://example.com/skin.png")
+            .unwrap();
+        services
+            .upload_skin(SkinModel::Classic, b"\x89PNG")
+            .unwrap();
+        services
+            .show_cape("1981aad3-73b6-4a49-8e01-9e55b1d5a0f3")
+            .unwrap();
+        assert!(matches!(services.hide_cape(), Err(Error::InvalidToken)));
+
+        let requests = transport.requests();
+        let summary: Vec<_> = requests
+            .iter()
+            .map(|request| (request.method, request.url.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (Method::Get, "http://localhost/minecraft/profile"),
+                (
+                    Method::Get,
+                    "http://localhost/minecraft/profile/name/jeb_/available"
+                ),
+                (Method::Post, "http://localhost/minecraft/profile/skins"),
+                (Method::Post, "http://localhost/minecraft/profile/skins"),
+                (
+                    Method::Put,
+                    "http://localhost/minecraft/profile/capes/active"
+                ),
+                (
+                    Method::Delete,
+                    "http://localhost/minecraft/profile/capes/active"
+                ),
+            ]
+        );
+        assert!(requests.iter().all(|request| request
+            .headers
+            .contains(&("Authorization".to_owned(), "Bearer token".to_owned()))));
+
+        let body: serde_json::Value =
+            serde_json::from_slice(requests[2].body.as_ref().unwrap()).unwrap();
+        assert_eq!(
+            body,
+            json!({"variant": "slim", "url": "https://example.com/skin.png"})
+        );
+        let upload = requests[3].body.as_ref().unwrap();
+        assert!(upload.ends_with(b"\r\n\r\n\x89PNG\r\n--uuid-mc-skin-upload--\r\n"));
+    }
+
+    #[test]
+    fn multipart_boundaries() {
+        assert_eq!(multipart_boundary(b"\x89PNG"), "uuid-mc-skin-upload");
+        assert_eq!(
+            multipart_boundary(b"\x89PNG--uuid-mc-skin-upload uuid-mc-skin-upload-1"),
+            "uuid-mc-skin-upload-2"
+        );
+
+        let client = MojangClient::with_transport(MockTransport::new());
+        let services = MinecraftServices::with_client(client, "token");
+        let request = services.skin_upload_request(SkinModel::Slim, b"--uuid-mc-skin-upload--");
+        assert!(request.headers.contains(&(
+            "Content-Type".to_owned(),
+            "multipart/form-data; boundary=uuid-mc-skin-upload-1".to_owned()
+        )));
+        assert!(request
+            .body
+            .unwrap()
+            .ends_with(b"--uuid-mc-skin-upload--\r\n--uuid-mc-skin-upload-1--\r\n"));
+    }
+}
@@ -0,0 +1,192 @@
+//! Decoding of the `textures` profile property into typed skin and cape information.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, GameProfile, OnlineUuid, ProfileProperty, Result, SkinModel};
+
+/// The name of the profile property that holds a player's textures.
+pub const TEXTURES_PROPERTY: &str = "textures";
+
+/// The decoded value of a `textures` profile property.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Textures {
+    /// The time at which the property was generated, in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    pub profile_id: OnlineUuid,
+    pub profile_name: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub signature_required: bool,
+    pub textures: TextureSet,
+}
+
+/// The textures a player has set. Either of them is absent if the player uses the default one.
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TextureSet {
+    #[serde(rename = "SKIN", default, skip_serializing_if = "Option::is_none")]
+    pub skin: Option<Texture>,
+    #[serde(rename = "CAPE", default, skip_serializing_if = "Option::is_none")]
+    pub cape: Option<Texture>,
+}
+
+/// A single texture, hosted by Mojang.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Texture {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<TextureMetadata>,
+}
+
+/// Extra information about a texture. Only skins have any.
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TextureMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+impl Textures {
+    /// Decodes the base64-encoded value of a `textures` property.
+    ///
+    /// # Errors
+    /// If the value isn't valid base64, or doesn't contain the expected JSON, an [`Error::InvalidTextures`] is returned.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use uuid_mc::{SkinModel, Textures};
+    ///
+    /// # fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    /// let value = "eyJ0aW1lc3RhbXAiOjE3MDAwMDAwMDAwMDAsInByb2ZpbGVJZCI6IjA2OWE3OWY0NDRlOTQ3MjZhNWJlZmNhOTBlMzhhYWY1Ii\
+    ///     wicHJvZmlsZU5hbWUiOiJOb3RjaCIsInRleHR1cmVzIjp7IlNLSU4iOnsidXJsIjoiaHR0cDovL3RleHR1cmVzLm1pbmVjcmFmdC5uZXQvdGV4dH\
+    ///     VyZS8yOTJlZjU2NyJ9fX0=";
+    /// let textures = Textures::decode(value)?;
+    ///
+    /// assert_eq!(textures.profile_name, "Notch");
+    /// assert_eq!(textures.skin_hash(), Some("292ef567"));
+    /// assert_eq!(textures.skin_model(), SkinModel::Classic);
+    /// assert_eq!(textures.cape_url(), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn decode(value: &str) -> Result<Self> {
+        let json = base64::engine::general_purpose::STANDARD
+            .decode(value)
+            .map_err(|x| Error::InvalidTextures(Box::new(x)))?;
+
+        serde_json::from_slice(&json).map_err(|x| Error::InvalidTextures(Box::new(x)))
+    }
+
+    /// Returns the URL of the player's skin, if they have one set.
+    pub fn skin_url(&self) -> Option<&str> {
+        self.textures.skin.as_ref().map(|skin| skin.url.as_str())
+    }
+
+    /// Returns the URL of the player's cape, if they have one.
+    pub fn cape_url(&self) -> Option<&str> {
+        self.textures.cape.as_ref().map(|cape| cape.url.as_str())
+    }
+
+    /// Returns the model of the player's skin, which is [`SkinModel::Classic`] if they have no skin set.
+    pub fn skin_model(&self) -> SkinModel {
+        self.textures
+            .skin
+            .as_ref()
+            .map_or(SkinModel::Classic, Texture::model)
+    }
+
+    /// Returns the hash of the player's skin, if they have one set.
+    pub fn skin_hash(&self) -> Option<&str> {
+        self.textures.skin.as_ref().and_then(Texture::hash)
+    }
+
+    /// Returns the hash of the player's cape, if they have one.
+    pub fn cape_hash(&self) -> Option<&str> {
+        self.textures.cape.as_ref().and_then(Texture::hash)
+    }
+}
+
+impl Texture {
+    /// Returns the texture's hash, which is the last path segment of its URL.
+    pub fn hash(&self) -> Option<&str> {
+        self.url.rsplit('/').next().filter(|hash| !hash.is_empty())
+    }
+
+    /// Returns the model this texture is meant for, which is only meaningful for skins.
+    pub fn model(&self) -> SkinModel {
+        let model = self.metadata.as_ref().and_then(|x| x.model.as_deref());
+        match model {
+            Some("slim") => SkinModel::Slim,
+            _ => SkinModel::Classic,
+        }
+    }
+}
+
+impl ProfileProperty {
+    /// Decodes this property's value as [`Textures`]. See [`Textures::decode`].
+    pub fn textures(&self) -> Result<Textures> {
+        Textures::decode(&self.value)
+    }
+}
+
+impl GameProfile {
+    /// Decodes the profile's `textures` property, if it has one. See [`Textures::decode`].
+    pub fn textures(&self) -> Result<Option<Textures>> {
+        self.property(TEXTURES_PROPERTY)
+            .map(ProfileProperty::textures)
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(json: &str) -> String {
+        base64::engine::general_purpose::STANDARD.encode(json)
+    }
+
+    #[test]
+    fn decode_textures() {
+        let value = encode(
+            r#"{
+                "timestamp": 1700000000000,
+                "profileId": "853c80ef3c3749fdaa49938b674adae6",
+                "profileName": "jeb_",
+                "signatureRequired": true,
+                "textures": {
+                    "SKIN": {
+                        "url": "http://textures.minecraft.net/texture/7fd9ba42a7c81eeea22f1524271ae85a8e045ce0af5a6ae16c6406ae917e68b5",
+                        "metadata": {"model": "slim"}
+                    },
+                    "CAPE": {"url": "http://textures.minecraft.net/texture/9e507afc56359978a3eb3e32367042b853cddd0995d17d0da995662913fb00f7"}
+                }
+            }"#,
+        );
+        let textures = Textures::decode(&value).unwrap();
+
+        assert_eq!(textures.timestamp, 1700000000000);
+        assert_eq!(textures.profile_name, "jeb_");
+        assert!(textures.signature_required);
+        assert_eq!(textures.skin_model(), SkinModel::Slim);
+        assert_eq!(
+            textures.skin_hash(),
+            Some("7fd9ba42a7c81eeea22f1524271ae85a8e045ce0af5a6ae16c6406ae917e68b5")
+        );
+        assert_eq!(
+            textures.cape_url(),
+            Some("http://textures.minecraft.net/texture/9e507afc56359978a3eb3e32367042b853cddd0995d17d0da995662913fb00f7")
+        );
+    }
+
+    #[test]
+    fn decode_invalid_textures() {
+        assert!(matches!(
+            Textures::decode("not base64!"),
+            Err(Error::InvalidTextures(_))
+        ));
+        assert!(matches!(
+            Textures::decode(&encode("{}")),
+            Err(Error::InvalidTextures(_))
+        ));
+    }
+}
@@ -0,0 +1,302 @@
+//! The HTTP transport abstraction used by every Mojang API call in this crate.
+//!
+//! The lookup functions only ever need to send a request and read back a status code and a body,
+//! so anything that can do that (a custom connection pool, a client configured with a proxy or
+//! custom TLS, a mock in tests) can be plugged in by implementing `HttpTransport` or
+//! `AsyncHttpTransport`.
+
+#[cfg(feature = "async")]
+use std::future::Future;
+#[cfg(feature = "online")]
+use std::io::Read;
+#[cfg(feature = "async")]
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// The error type that transports return when a request could not be completed at all.
+pub type TransportError = crate::BoxError;
+
+/// The HTTP method of an [`HttpRequest`].
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
+
+impl Method {
+    /// Returns the method's name, as it appears in an HTTP request line.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Get => "GET",
+            Self::Post => "POST",
+            Self::Put => "PUT",
+            Self::Delete => "DELETE",
+        }
+    }
+}
+
+/// An HTTP request that this crate asks a transport to perform.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+    /// The maximum amount of time the whole request may take, if any.
+    pub timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+    /// Creates a new request with the given method and URL, without any headers or body.
+    pub fn new(method: Method, url: impl Into<String>) -> Self {
+        Self {
+            method,
+            url: url.into(),
+            headers: Vec::new(),
+            body: None,
+            timeout: None,
+        }
+    }
+
+    /// Creates a new `GET` request for the given URL, without any headers.
+    pub fn get(url: impl Into<String>) -> Self {
+        Self::new(Method::Get, url)
+    }
+
+    /// Creates a new `POST` request for the given URL, with a JSON body.
+    pub fn post_json(url: impl Into<String>, body: Vec<u8>) -> Self {
+        Self {
+            method: Method::Post,
+            url: url.into(),
+            headers: vec![("Content-Type".to_owned(), "application/json".to_owned())],
+            body: Some(body),
+            timeout: None,
+        }
+    }
+
+    /// Creates a new `POST` request for the given URL, with a URL-encoded form body.
+    pub fn post_form(url: impl Into<String>, body: Vec<u8>) -> Self {
+        Self {
+            method: Method::Post,
+            url: url.into(),
+            headers: vec![(
+                "Content-Type".to_owned(),
+                "application/x-www-form-urlencoded".to_owned(),
+            )],
+            body: Some(body),
+            timeout: None,
+        }
+    }
+
+    /// Adds a header to the request.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+/// The response to an [`HttpRequest`].
+///
+/// Transports must return a response for every status code, including 4xx and 5xx ones;
+/// [`TransportError`]s are reserved for requests that never got a response.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Returns the value of the first header with the given name, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A blocking HTTP transport.
+pub trait HttpTransport {
+    /// Performs the request, returning the server's response.
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse, TransportError>;
+}
+
+impl<T: HttpTransport + ?Sized> HttpTransport for &T {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse, TransportError> {
+        (**self).send(request)
+    }
+}
+
+/// A non-blocking HTTP transport.
+#[cfg(feature = "async")]
+pub trait AsyncHttpTransport {
+    /// Performs the request, returning the server's response.
+    fn send(
+        &self,
+        request: HttpRequest,
+    ) -> impl Future<Output = Result<HttpResponse, TransportError>> + Send;
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncHttpTransport + Sync + ?Sized> AsyncHttpTransport for &T {
+    fn send(
+        &self,
+        request: HttpRequest,
+    ) -> impl Future<Output = Result<HttpResponse, TransportError>> + Send {
+        (**self).send(request)
+    }
+}
+
+/// The default blocking transport, backed by a [`ureq::Agent`].
+#[cfg(feature = "online")]
+#[derive(Clone, Debug)]
+pub struct UreqTransport {
+    agent: ureq::Agent,
+}
+
+#[cfg(feature = "online")]
+impl UreqTransport {
+    /// Creates a transport that sends its requests through the given agent.
+    pub fn new(agent: ureq::Agent) -> Self {
+        Self { agent }
+    }
+}
+
+#[cfg(feature = "online")]
+impl Default for UreqTransport {
+    fn default() -> Self {
+        Self::new(ureq::agent())
+    }
+}
+
+#[cfg(feature = "online")]
+impl HttpTransport for UreqTransport {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse, TransportError> {
+        let mut builder = self.agent.request(request.method.as_str(), &request.url);
+        for (name, value) in &request.headers {
+            builder = builder.set(name, value);
+        }
+        if let Some(timeout) = request.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        let response = match request.body {
+            Some(body) => builder.send_bytes(&body),
+            None => builder.call(),
+        };
+
+        let response = match response {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(ureq::Error::Transport(x)) => return Err(Box::new(x)),
+        };
+
+        let status = response.status();
+        let headers = response
+            .headers_names()
+            .into_iter()
+            .filter_map(|name| {
+                let value = response.header(&name)?.to_owned();
+                Some((name, value))
+            })
+            .collect();
+        let mut body = Vec::new();
+        response.into_reader().read_to_end(&mut body)?;
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+/// The default non-blocking transport, backed by a [`reqwest::Client`].
+#[cfg(feature = "async")]
+#[derive(Clone, Debug, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "async")]
+impl ReqwestTransport {
+    /// Creates a transport that sends its requests through the given client.
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncHttpTransport for ReqwestTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, TransportError> {
+        let method = match request.method {
+            Method::Get => reqwest::Method::GET,
+            Method::Post => reqwest::Method::POST,
+            Method::Put => reqwest::Method::PUT,
+            Method::Delete => reqwest::Method::DELETE,
+        };
+
+        let mut builder = self.client.request(method, &request.url);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+        if let Some(timeout) = request.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        let response = builder.send().await?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                let value = value.to_str().ok()?.to_owned();
+                Some((name.as_str().to_owned(), value))
+            })
+            .collect();
+        let body = response.bytes().await?.to_vec();
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+/// The transport that clients use when none is specified.
+///
+/// It is the same type regardless of which features are enabled: it implements [`HttpTransport`]
+/// (through a `UreqTransport`) when the `online` feature is enabled, and `AsyncHttpTransport`
+/// (through a `ReqwestTransport`, created on first use) when the `async` feature is enabled.
+#[derive(Clone, Debug, Default)]
+pub struct DefaultTransport {
+    #[cfg(feature = "online")]
+    blocking: UreqTransport,
+    #[cfg(feature = "async")]
+    non_blocking: OnceLock<ReqwestTransport>,
+}
+
+#[cfg(feature = "online")]
+impl HttpTransport for DefaultTransport {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse, TransportError> {
+        self.blocking.send(request)
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncHttpTransport for DefaultTransport {
+    fn send(
+        &self,
+        request: HttpRequest,
+    ) -> impl Future<Output = Result<HttpResponse, TransportError>> + Send {
+        self.non_blocking
+            .get_or_init(ReqwestTransport::default)
+            .send(request)
+    }
+}
@@ -0,0 +1,285 @@
+//! Validated Minecraft usernames.
+
+use alloc::string::String;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+use core::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Error, Result};
+
+/// The minimum length of a username that Mojang accepts today.
+pub const MIN_USERNAME_LENGTH: usize = 3;
+
+/// The maximum length of a username, which is also the longest name the game protocol allows.
+pub const MAX_USERNAME_LENGTH: usize = 16;
+
+/// A Minecraft username.
+///
+/// Usernames compare and hash case-insensitively, like Minecraft itself treats them,
+/// but keep the capitalization they were created with. Only ASCII letters are case-folded,
+/// which is the rule used for usernames throughout this crate.
+/// A `Username` dereferences to a `&str`, so it can be passed to every lookup that takes a username.
+///
+/// Deserialization validates usernames like [`Username::new`] does. To accept the usernames that
+/// [`Username::new_lenient`] does instead, use the [`lenient`] module.
+///
+/// # Examples
+/// ```rust
+/// use uuid_mc::Username;
+///
+/// let username: Username = "Notch".parse().unwrap();
+/// assert_eq!(username, "notch".parse().unwrap());
+/// assert_eq!(username.to_string(), "Notch");
+///
+/// assert!("no spaces".parse::<Username>().is_err());
+/// assert!(Username::new_lenient("Ex-Player").is_ok());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Username(String);
+
+impl Username {
+    /// Creates a username that follows Mojang's current rules: 3 to 16 characters, each of which is
+    /// an ASCII letter, a digit or an underscore.
+    ///
+    /// # Errors
+    /// If the username doesn't follow the rules, an [`Error::InvalidUsername`] is returned.
+    pub fn new(username: impl Into<String>) -> Result<Self> {
+        let username = username.into();
+        let valid = (MIN_USERNAME_LENGTH..=MAX_USERNAME_LENGTH).contains(&username.len())
+            && username
+                .bytes()
+                .all(|x| x.is_ascii_alphanumeric() || x == b'_');
+
+        if valid {
+            Ok(Self(username))
+        } else {
+            Err(Error::InvalidUsername)
+        }
+    }
+
+    /// Creates a username with relaxed rules, for legacy accounts and offline servers:
+    /// 1 to 16 characters, none of which are whitespace or control characters.
+    ///
+    /// # Errors
+    /// If the username doesn't follow the relaxed rules, an [`Error::InvalidUsername`] is returned.
+    pub fn new_lenient(username: impl Into<String>) -> Result<Self> {
+        let username = username.into();
+        let length = username.chars().count();
+        let valid = (1..=MAX_USERNAME_LENGTH).contains(&length)
+            && !username
+                .chars()
+                .any(|x| x.is_whitespace() || x.is_control());
+
+        if valid {
+            Ok(Self(username))
+        } else {
+            Err(Error::InvalidUsername)
+        }
+    }
+
+    /// Returns the username, with the capitalization it was created with.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Consumes the username, returning the underlying string.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl PartialEq for Username {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_ignore_ascii_case(&other.0)
+    }
+}
+
+impl Eq for Username {}
+
+impl Hash for Username {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for byte in self.0.bytes() {
+            state.write_u8(byte.to_ascii_lowercase());
+        }
+        state.write_u8(0xff);
+    }
+}
+
+impl Deref for Username {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Username {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Username {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Username {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<String> for Username {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        Self::new(value)
+    }
+}
+
+impl TryFrom<&str> for Username {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        Self::new(value)
+    }
+}
+
+impl From<Username> for String {
+    fn from(username: Username) -> Self {
+        username.0
+    }
+}
+
+impl Serialize for Username {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Username {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> core::result::Result<Self, D::Error> {
+        let username = String::deserialize(deserializer)?;
+        Self::new(username).map_err(serde::de::Error::custom)
+    }
+}
+
+/// A module for use with `#[serde(with = "...")]`, which deserializes a [`Username`] following the relaxed
+/// rules of [`Username::new_lenient`], so that usernames of legacy accounts survive a round trip.
+///
+/// # Examples
+/// ```rust
+/// use serde::{Deserialize, Serialize};
+/// use uuid_mc::Username;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Player {
+///     #[serde(with = "uuid_mc::username::lenient")]
+///     name: Username,
+/// }
+/// ```
+pub mod lenient {
+    use super::*;
+
+    /// Serializes a username as a string, like its [`Serialize`] implementation does.
+    pub fn serialize<S: Serializer>(
+        username: &Username,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        username.serialize(serializer)
+    }
+
+    /// Deserializes a username from a string, validating it with [`Username::new_lenient`].
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Username, D::Error> {
+        let username = String::deserialize(deserializer)?;
+        Username::new_lenient(username).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    #[test]
+    fn validation() {
+        for valid in [
+            "Notch",
+            "jeb_",
+            "abc",
+            "boolean_coercion",
+            "0123456789abcdef",
+        ] {
+            assert!(Username::new(valid).is_ok(), "{valid}");
+        }
+        for invalid in [
+            "",
+            "ab",
+            "no spaces",
+            "0123456789abcdefg",
+            "Ex-Player",
+            "ñandú",
+        ] {
+            assert!(
+                matches!(Username::new(invalid), Err(Error::InvalidUsername)),
+                "{invalid}"
+            );
+        }
+
+        assert!(Username::new_lenient("Ex-Player").is_ok());
+        assert!(Username::new_lenient("ab").is_ok());
+        assert!(Username::new_lenient("ñandú").is_ok());
+        assert!(Username::new_lenient("").is_err());
+        assert!(Username::new_lenient("no spaces").is_err());
+        assert!(Username::new_lenient("0123456789abcdefg").is_err());
+    }
+
+    #[test]
+    fn case_insensitivity() {
+        let lower = Username::new("bool").unwrap();
+        let upper = Username::new("BOOL").unwrap();
+        assert_eq!(lower, upper);
+        assert_eq!(upper.as_str(), "BOOL");
+
+        let set: HashSet<_> = [lower, upper, Username::new("BoOl").unwrap()].into();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn serde() {
+        let username: Username = serde_json::from_str(r#""Notch""#).unwrap();
+        assert_eq!(serde_json::to_string(&username).unwrap(), r#""Notch""#);
+        assert!(serde_json::from_str::<Username>(r#""no spaces""#).is_err());
+
+        assert!(serde_json::from_str::<Username>(r#""ab""#).is_err());
+
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Player {
+            #[serde(with = "lenient")]
+            name: Username,
+        }
+
+        let player = Player {
+            name: Username::new_lenient("Ex-Player").unwrap(),
+        };
+        let json = serde_json::to_string(&player).unwrap();
+        assert_eq!(json, r#"{"name":"Ex-Player"}"#);
+        assert_eq!(
+            serde_json::from_str::<Player>(&json).unwrap().name.as_str(),
+            "Ex-Player"
+        );
+        assert!(serde_json::from_str::<Player>(r#"{"name":"ab"}"#).is_ok());
+        assert!(serde_json::from_str::<Player>(r#"{"name":"no spaces"}"#).is_err());
+    }
+}
@@ -0,0 +1,153 @@
+//! Verification of signed profile properties against a Yggdrasil public key.
+
+use std::sync::OnceLock;
+
+use base64::Engine;
+use rsa::pkcs1v15::{Signature, VerifyingKey};
+use rsa::pkcs8::DecodePublicKey;
+use rsa::signature::Verifier;
+use rsa::RsaPublicKey;
+use sha1::Sha1;
+
+use crate::{Error, GameProfile, ProfileProperty, Result};
+
+/// Mojang's Yggdrasil session public key, in DER format, as shipped with authlib.
+const MOJANG_KEY_DER: &[u8] = include_bytes!("yggdrasil_session_pubkey.der");
+
+/// A public key that profile property signatures can be verified against.
+///
+/// Use [`YggdrasilKey::mojang`] for properties that come from Mojang, or load the key of a
+/// third-party Yggdrasil server (such as one using authlib-injector) with [`YggdrasilKey::from_der`]
+/// or [`YggdrasilKey::from_pem`].
+#[derive(Clone, Debug)]
+pub struct YggdrasilKey(VerifyingKey<Sha1>);
+
+impl YggdrasilKey {
+    /// Returns Mojang's Yggdrasil session key, which is bundled with this library.
+    pub fn mojang() -> &'static Self {
+        static KEY: OnceLock<YggdrasilKey> = OnceLock::new();
+        KEY.get_or_init(|| Self::from_der(MOJANG_KEY_DER).expect("the bundled key is valid"))
+    }
+
+    /// Loads a key from its DER-encoded `SubjectPublicKeyInfo`.
+    ///
+    /// # Errors
+    /// If the data isn't a valid RSA public key, an [`Error::InvalidKey`] is returned.
+    pub fn from_der(der: &[u8]) -> Result<Self> {
+        RsaPublicKey::from_public_key_der(der)
+            .map(Self::from)
+            .map_err(|x| Error::InvalidKey(Box::new(x)))
+    }
+
+    /// Loads a key from a PEM-encoded `SubjectPublicKeyInfo` (`-----BEGIN PUBLIC KEY-----`).
+    ///
+    /// # Errors
+    /// If the data isn't a valid RSA public key, an [`Error::InvalidKey`] is returned.
+    pub fn from_pem(pem: &str) -> Result<Self> {
+        RsaPublicKey::from_public_key_pem(pem)
+            .map(Self::from)
+            .map_err(|x| Error::InvalidKey(Box::new(x)))
+    }
+}
+
+impl From<RsaPublicKey> for YggdrasilKey {
+    fn from(key: RsaPublicKey) -> Self {
+        Self(VerifyingKey::new(key))
+    }
+}
+
+impl ProfileProperty {
+    /// Verifies this property's signature against Mojang's Yggdrasil key.
+    ///
+    /// # Errors
+    /// If the property is unsigned, or its signature doesn't match its value, an [`Error::InvalidSignature`] is returned.
+    pub fn verify(&self) -> Result<()> {
+        self.verify_with(YggdrasilKey::mojang())
+    }
+
+    /// Verifies this property's signature against the provided key.
+    ///
+    /// # Errors
+    /// See [`verify`](Self::verify).
+    pub fn verify_with(&self, key: &YggdrasilKey) -> Result<()> {
+        let signature = self.signature.as_deref().ok_or(Error::InvalidSignature)?;
+        let signature = base64::engine::general_purpose::STANDARD
+            .decode(signature)
+            .map_err(|_| Error::InvalidSignature)?;
+        let signature =
+            Signature::try_from(signature.as_slice()).map_err(|_| Error::InvalidSignature)?;
+
+        key.0
+            .verify(self.value.as_bytes(), &signature)
+            .map_err(|_| Error::InvalidSignature)
+    }
+}
+
+impl GameProfile {
+    /// Verifies the signatures of all of this profile's properties against Mojang's Yggdrasil key.
+    ///
+    /// # Errors
+    /// If any of the properties is unsigned or has a signature that doesn't match, an [`Error::InvalidSignature`] is returned.
+    pub fn verify(&self) -> Result<()> {
+        self.verify_with(YggdrasilKey::mojang())
+    }
+
+    /// Verifies the signatures of all of this profile's properties against the provided key.
+    ///
+    /// # Errors
+    /// See [`verify`](Self::verify).
+    pub fn verify_with(&self, key: &YggdrasilKey) -> Result<()> {
+        self.properties
+            .iter()
+            .try_for_each(|property| property.verify_with(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_KEY: &str = "-----BEGIN PUBLIC KEY-----
+MIGfMA0GCSqGSIb3DQEBAQUAA4GNADCBiQKBgQDBJJlV28g2WZdgvvizi+k/3aZy
+7jb9eah9FxcCN22gwlanqjdbbBgYQ//WlosE5rimABmnu015ObnaL+4m0Q2gtv9g
+3tmgWj54WAYvKf67PPUHPmfJ4VRRy164VwMcqm6silqDNmIRXDcwpod4FoEy9E8q
+DvXPK/gg90AqUTQqvQIDAQAB
+-----END PUBLIC KEY-----";
+
+    fn property(signature: Option<&str>) -> ProfileProperty {
+        ProfileProperty {
+            name: "textures".to_owned(),
+            value: "eyJ0aW1lc3RhbXAiOjE3MDAwMDAwMDAwMDAsInByb2ZpbGVJZCI6IjA2OWE3OWY0NDRlOTQ3MjZhNWJlZmNhOTBlMzhhYWY1IiwicHJvZmlsZU5hbWUiOiJOb3RjaCIsInRleHR1cmVzIjp7fX0=".to_owned(),
+            signature: signature.map(str::to_owned),
+        }
+    }
+
+    #[test]
+    fn mojang_key() {
+        YggdrasilKey::mojang();
+    }
+
+    #[test]
+    fn verify_signatures() {
+        let key = YggdrasilKey::from_pem(TEST_KEY).unwrap();
+        let signed = property(Some("UPs6eQQ4u7UEKok+ZaLH25qQyL1v0xf+6jAeLo34IvVTOmB5UtX998GYV5Zc5GAANzYUFo8HGrmpE7IwrVCmogL0E41mBA1AVPN3qm0GDOCxorZErCiAlW6wlFYNCeozuta2/J3xVhiGd3TVKI4qbN1plLA2kSurJWZrnSpZC+Q="));
+        assert!(signed.verify_with(&key).is_ok());
+        assert!(matches!(signed.verify(), Err(Error::InvalidSignature)));
+
+        let mut forged = signed.clone();
+        forged.value.replace_range(..1, "f");
+        assert!(matches!(
+            forged.verify_with(&key),
+            Err(Error::InvalidSignature)
+        ));
+
+        assert!(matches!(
+            property(None).verify_with(&key),
+            Err(Error::InvalidSignature)
+        ));
+        assert!(matches!(
+            property(Some("not base64!")).verify_with(&key),
+            Err(Error::InvalidSignature)
+        ));
+    }
+}